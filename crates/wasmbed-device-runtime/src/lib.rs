@@ -5,6 +5,16 @@
 //!
 //! This crate provides a no_std compatible runtime for executing
 //! WebAssembly applications on embedded devices.
+//!
+//! WASM execution happens here, on-device, not on the gateway - the gateway
+//! only relays the deploy/stop/prepare/commit/abort protocol messages over
+//! TLS (see `wasmbed-tls-utils::GatewayServer` and
+//! `wasmbed-gateway::http_api`). A `RuntimeManager` abstraction over runtime
+//! "kinds" was built for an earlier request in the disconnected
+//! `retrospect/crates/wasmbed-gateway` tree (no caller anywhere in
+//! `crates/`) and was removed rather than wired in: the gateway has no WASM
+//! runtime of its own to manage, so that abstraction had nothing real to sit
+//! in front of. `WasmRuntime` below is the one runtime that actually exists.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Bounds-checked host/guest memory access and the `env.malloc`/`env.free`
+//! allocator implementations backing them. All host functions that touch
+//! guest bytes should go through `read_bytes`/`write_bytes` here rather than
+//! calling `Memory::read`/`Memory::write` directly, so an out-of-range
+//! `(ptr, len)` from an untrusted guest is rejected instead of silently
+//! truncated or ignored.
+
+use wasmi::{AsContext, Caller, Memory, Trap};
+
+use crate::wasm_runtime::WasmRuntimeError;
+
+/// Per-instance allocator state for `env.malloc`/`env.free`, stored as the
+/// `Store`'s host state so host functions can reach it via `Caller`.
+pub struct HostState {
+    /// Next unused byte in the instance's linear memory (bump cursor).
+    cursor: u32,
+    /// Freed blocks available for reuse, as `(block_start, size)` pairs.
+    free_list: Vec<(u32, u32)>,
+    /// Ceiling on `cursor` (and therefore on total bytes ever allocated),
+    /// taken from `WasmRuntimeConfig::max_memory_per_app`.
+    max_memory_bytes: usize,
+}
+
+impl HostState {
+    pub fn new(max_memory_bytes: usize) -> Self {
+        Self { cursor: 0, free_list: Vec::new(), max_memory_bytes }
+    }
+
+    /// Ceiling passed in at construction, used to derive `MemoryInfo::max_pages`.
+    pub fn max_memory_bytes(&self) -> usize {
+        self.max_memory_bytes
+    }
+
+    /// Reset to a freshly-instantiated state, for instance-pool reuse.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+        self.free_list.clear();
+    }
+}
+
+/// Size, in bytes, of the length header `malloc` writes just before each
+/// returned pointer so `free` can recover the block's size without needing
+/// it passed back in.
+const HEADER_SIZE: u32 = 4;
+
+/// Read `len` bytes starting at `ptr` out of `memory`, rejecting the read if
+/// it would run past the end of the guest's current linear memory.
+pub fn read_bytes(memory: &Memory, store: impl AsContext, ptr: u32, len: u32) -> Result<Vec<u8>, WasmRuntimeError> {
+    let end = (ptr as u64).checked_add(len as u64)
+        .ok_or(WasmRuntimeError::MemoryLimitExceeded(len as usize))?;
+    if end > memory.data_size(&store) as u64 {
+        return Err(WasmRuntimeError::MemoryLimitExceeded(len as usize));
+    }
+
+    let mut buf = vec![0u8; len as usize];
+    memory.read(&store, ptr as usize, &mut buf)
+        .map_err(|e| WasmRuntimeError::ExecutionError(format!("guest memory read failed: {e}")))?;
+    Ok(buf)
+}
+
+/// Write `bytes` starting at `ptr` into `memory`, rejecting the write if it
+/// would run past the end of the guest's current linear memory.
+pub fn write_bytes(memory: &Memory, mut store: impl wasmi::AsContextMut, ptr: u32, bytes: &[u8]) -> Result<(), WasmRuntimeError> {
+    let end = (ptr as u64).checked_add(bytes.len() as u64)
+        .ok_or(WasmRuntimeError::MemoryLimitExceeded(bytes.len()))?;
+    if end > memory.data_size(&store) as u64 {
+        return Err(WasmRuntimeError::MemoryLimitExceeded(bytes.len()));
+    }
+
+    memory.write(&mut store, ptr as usize, bytes)
+        .map_err(|e| WasmRuntimeError::ExecutionError(format!("guest memory write failed: {e}")))?;
+    Ok(())
+}
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> Result<Memory, Trap> {
+    caller
+        .get_export("memory")
+        .and_then(|ext| ext.into_memory())
+        .ok_or_else(|| Trap::new("host function called on an instance with no 'memory' export"))
+}
+
+/// `env.malloc` — bump/free-list allocator over the instance's own linear
+/// memory. Reuses a free block if one is large enough, otherwise grows
+/// memory (bounded by `HostState::max_memory_bytes`) and bumps the cursor.
+/// Traps rather than returning a bogus pointer if the per-app memory limit
+/// would be exceeded.
+pub fn malloc(mut caller: Caller<'_, HostState>, size: i32) -> Result<i32, Trap> {
+    if size <= 0 {
+        return Ok(0);
+    }
+    let size = size as u32;
+    let memory = guest_memory(&mut caller)?;
+
+    if let Some(pos) = caller.data().free_list.iter().position(|&(_, len)| len >= size) {
+        let (block_start, len) = caller.data_mut().free_list.remove(pos);
+        if len > size {
+            caller.data_mut().free_list.push((block_start + HEADER_SIZE + size, len - size));
+        }
+        write_header(&memory, &mut caller, block_start, size)?;
+        return Ok((block_start + HEADER_SIZE) as i32);
+    }
+
+    let block_start = caller.data().cursor;
+    let required_end = block_start as u64 + HEADER_SIZE as u64 + size as u64;
+    if required_end > caller.data().max_memory_bytes as u64 {
+        return Err(Trap::new("malloc: application memory limit exceeded"));
+    }
+
+    let current_bytes = memory.data_size(&caller) as u64;
+    if required_end > current_bytes {
+        let additional_pages = ((required_end - current_bytes) + 65535) / 65536;
+        memory
+            .grow(&mut caller, additional_pages as u32)
+            .map_err(|_| Trap::new("malloc: failed to grow guest memory"))?;
+    }
+
+    write_header(&memory, &mut caller, block_start, size)?;
+    caller.data_mut().cursor = block_start + HEADER_SIZE + size;
+    Ok((block_start + HEADER_SIZE) as i32)
+}
+
+/// `env.free` — returns a previously-`malloc`'d block to the free list
+/// (recovering its size from the header `malloc` wrote just before it) so a
+/// later allocation can reuse the space instead of growing memory further.
+pub fn free(mut caller: Caller<'_, HostState>, ptr: i32) {
+    if ptr < HEADER_SIZE as i32 {
+        return;
+    }
+    let block_start = ptr as u32 - HEADER_SIZE;
+
+    let Ok(memory) = guest_memory(&mut caller) else { return };
+    let Ok(len) = read_header(&memory, &caller, block_start) else { return };
+
+    caller.data_mut().free_list.push((block_start, len));
+}
+
+fn write_header(memory: &Memory, caller: &mut Caller<'_, HostState>, block_start: u32, size: u32) -> Result<(), Trap> {
+    write_bytes(memory, caller, block_start, &size.to_le_bytes())
+        .map_err(|_| Trap::new("malloc: failed to write block header"))
+}
+
+fn read_header(memory: &Memory, caller: &Caller<'_, HostState>, block_start: u32) -> Result<u32, Trap> {
+    let bytes = read_bytes(memory, caller, block_start, HEADER_SIZE)
+        .map_err(|_| Trap::new("free: failed to read block header"))?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
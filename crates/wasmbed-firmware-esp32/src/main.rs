@@ -11,6 +11,8 @@ mod wasmbed_client;
 mod handlers;
 mod memory;
 mod wasm_runtime;
+mod wasm_backend;
+mod host_abi;
 mod application_manager;
 mod security;
 mod allocator;
@@ -0,0 +1,374 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Pluggable execution backend for `WasmRuntime`. The default (and
+//! currently only) implementation, `WasmiBackend`, runs modules through
+//! wasmi; a register-machine interpreter (e.g. a PolkaVM-style RISC-V
+//! backend) can be added later behind a cargo feature by implementing
+//! `WasmBackend` without touching `execute_function`, `get_memory_info`,
+//! or the metrics paths in `wasm_runtime`.
+
+use wasmi::{
+    Caller, Engine, ExternType, Func, FuncType, Instance, Linker, Memory, Module, Store,
+    TypedFunc, Value, ValueType,
+};
+use log::{info, warn};
+use std::collections::BTreeMap;
+
+use crate::host_abi::{self, HostState};
+use crate::wasm_runtime::{MemoryInfo, WasmRuntimeConfig, WasmRuntimeError};
+
+/// Which `WasmBackend` a `WasmRuntime` is configured to use. Selecting a
+/// variant other than `Wasmi` requires building with the matching cargo
+/// feature enabled; until a second backend lands, `Wasmi` is the only one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WasmBackendKind {
+    #[default]
+    Wasmi,
+}
+
+/// Outcome of a single function call through a `WasmBackend`.
+pub struct CallOutcome {
+    pub results: Vec<Value>,
+    pub fuel_consumed: u64,
+}
+
+/// Abstracts module compilation, instantiation, function calls, and memory
+/// introspection behind one interface so `WasmRuntime` isn't hardwired to a
+/// specific interpreter's concrete types.
+pub trait WasmBackend {
+    /// A parsed, validated module, cheap to re-instantiate.
+    type Module;
+    /// A running instance of a `Module`.
+    type Instance;
+
+    /// Construct a fresh backend configured per `config` (e.g. engine-level
+    /// feature flags like memory64, which must be fixed at engine creation).
+    fn new(config: &WasmRuntimeConfig) -> Self;
+
+    /// Parse and validate `wasm_bytes` into a `Module`.
+    fn compile(&self, wasm_bytes: &[u8]) -> Result<Self::Module, WasmRuntimeError>;
+
+    /// Validate `module` against deployment policy before it is
+    /// instantiated: declared memory limits within `max_memory_bytes`, a
+    /// 64-bit memory only if `enable_memory64` is set, every import
+    /// resolvable by the host, and no more than `max_exports` exported
+    /// functions. Modules that use a WASM proposal this backend's engine
+    /// doesn't enable are already rejected by `compile`, since parsing
+    /// itself fails for them.
+    fn validate(&self, module: &Self::Module, max_memory_bytes: usize, max_exports: usize, enable_memory64: bool) -> Result<(), WasmRuntimeError>;
+
+    /// Instantiate `module`, running its start function if it has one.
+    /// `max_memory_bytes` bounds how much linear memory `env.malloc` will
+    /// let the instance grow into.
+    fn instantiate(&self, module: &Self::Module, max_memory_bytes: usize) -> Result<Self::Instance, WasmRuntimeError>;
+
+    /// Call `function_name` on `instance` with `args`, metering execution
+    /// against `fuel_budget` units of fuel. Returns `WasmRuntimeError::TypeMismatch`
+    /// if `args` doesn't match the function's declared signature.
+    fn call(
+        &self,
+        instance: &mut Self::Instance,
+        function_name: &str,
+        args: &[Value],
+        fuel_budget: u64,
+    ) -> Result<CallOutcome, WasmRuntimeError>;
+
+    /// Zero `instance`'s linear memory and reset its allocator state so it
+    /// can be handed back out of the instance pool. Memory keeps its current
+    /// size (it is not shrunk to the initial page count), data segments are
+    /// not re-applied, and globals keep their values.
+    fn reset_for_reuse(&self, instance: &mut Self::Instance);
+
+    /// Current linear memory footprint of `instance`, in bytes.
+    fn memory_usage_bytes(&self, instance: &Self::Instance) -> usize;
+
+    /// Page-count memory info for `instance`, if it exports memory.
+    fn memory_info(&self, instance: &Self::Instance) -> Option<MemoryInfo>;
+}
+
+/// wasmi 0.17-backed implementation of `WasmBackend`.
+pub struct WasmiBackend {
+    engine: Engine,
+}
+
+/// A wasmi-instantiated module, along with everything needed to call its
+/// exports and introspect its memory.
+pub struct WasmiInstance {
+    instance: Instance,
+    store: Store<HostState>,
+    memory: Option<Memory>,
+    /// Exported functions, keyed by name, alongside their signature so
+    /// `call` can validate arguments against it.
+    functions: BTreeMap<String, (Func, FuncType)>,
+    /// Cached `TypedFunc<(), ()>` for the common zero-arg/zero-result case,
+    /// avoiding the dynamic `Func::call` path when it applies.
+    nullary_functions: BTreeMap<String, TypedFunc<(), ()>>,
+    /// Linear memory page count at instantiation time.
+    initial_memory_pages: u32,
+}
+
+impl WasmiInstance {
+    pub fn initial_memory_pages(&self) -> u32 {
+        self.initial_memory_pages
+    }
+}
+
+impl WasmiBackend {
+    fn create_engine(enable_memory64: bool) -> Engine {
+        let mut engine_config = wasmi::Config::default();
+        // Enable bulk memory operations
+        engine_config.wasm_bulk_memory(true);
+        // Enable reference types
+        engine_config.wasm_reference_types(true);
+        // Meter fuel so execution can be interrupted deterministically; see
+        // `call` for how the per-call budget is spent.
+        engine_config.consume_fuel(true);
+        // Disabled by default: widens linear memory to 64-bit addressing,
+        // for devices that can actually back a larger address space.
+        engine_config.wasm_memory64(enable_memory64);
+
+        Engine::new(&engine_config)
+    }
+
+    /// Add host functions to the linker. `malloc`/`free` are backed by the
+    /// bump/free-list allocator in `host_abi`, and `console.log` reads
+    /// guest memory through the same bounds-checked path instead of
+    /// silently dropping an out-of-range `(ptr, len)`.
+    fn add_host_functions(&self, linker: &mut Linker<HostState>) -> Result<(), WasmRuntimeError> {
+        linker
+            .func_wrap("console", "log", |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                let Some(memory) = caller.get_export("memory").and_then(|ext| ext.into_memory()) else {
+                    return;
+                };
+                match host_abi::read_bytes(&memory, &caller, ptr as u32, len as u32) {
+                    Ok(bytes) => info!("WASM console.log: {}", String::from_utf8_lossy(&bytes)),
+                    Err(e) => warn!("console.log with out-of-bounds (ptr, len): {}", e),
+                }
+            })
+            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add console.log: {}", e)))?;
+
+        linker
+            .func_wrap("env", "malloc", host_abi::malloc)
+            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add malloc: {}", e)))?;
+
+        linker
+            .func_wrap("env", "free", host_abi::free)
+            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add free: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn value_type_of(value: &Value) -> ValueType {
+        match value {
+            Value::I32(_) => ValueType::I32,
+            Value::I64(_) => ValueType::I64,
+            Value::F32(_) => ValueType::F32,
+            Value::F64(_) => ValueType::F64,
+            Value::FuncRef(_) => ValueType::FuncRef,
+            Value::ExternRef(_) => ValueType::ExternRef,
+        }
+    }
+
+    fn default_value_for(value_type: &ValueType) -> Value {
+        match value_type {
+            ValueType::I32 => Value::I32(0),
+            ValueType::I64 => Value::I64(0),
+            ValueType::F32 => Value::F32(0.0.into()),
+            ValueType::F64 => Value::F64(0.0.into()),
+            ValueType::FuncRef => Value::FuncRef(wasmi::FuncRef::null()),
+            ValueType::ExternRef => Value::ExternRef(wasmi::ExternRef::null()),
+        }
+    }
+
+    fn check_memory_limits(memory_type: &wasmi::MemoryType, max_memory_bytes: usize, enable_memory64: bool) -> Result<(), WasmRuntimeError> {
+        if memory_type.is_64() && !enable_memory64 {
+            return Err(WasmRuntimeError::ValidationError(
+                "module declares a 64-bit memory, but enable_memory64 is off".to_string(),
+            ));
+        }
+
+        let max_pages = max_memory_bytes as u64 / WASM_PAGE_SIZE_BYTES;
+
+        if memory_type.initial() as u64 > max_pages {
+            return Err(WasmRuntimeError::ValidationError(format!(
+                "module declares {} initial memory pages, exceeding the {} allowed by max_memory_per_app",
+                memory_type.initial(),
+                max_pages
+            )));
+        }
+        if let Some(maximum) = memory_type.maximum() {
+            if maximum as u64 > max_pages {
+                return Err(WasmRuntimeError::ValidationError(format!(
+                    "module declares a maximum of {maximum} memory pages, exceeding the {max_pages} allowed by max_memory_per_app"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `(module, name)` pairs of host functions `add_host_functions` registers;
+/// a module importing anything outside this set can never instantiate, so
+/// `validate` rejects it up front.
+const SUPPORTED_IMPORTS: &[(&str, &str)] = &[
+    ("console", "log"),
+    ("env", "malloc"),
+    ("env", "free"),
+];
+
+const WASM_PAGE_SIZE_BYTES: u64 = 65536;
+
+impl WasmBackend for WasmiBackend {
+    type Module = Module;
+    type Instance = WasmiInstance;
+
+    fn new(config: &WasmRuntimeConfig) -> Self {
+        Self { engine: Self::create_engine(config.enable_memory64) }
+    }
+
+    fn compile(&self, wasm_bytes: &[u8]) -> Result<Self::Module, WasmRuntimeError> {
+        Module::new(&self.engine, wasm_bytes)
+            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to parse module: {}", e)))
+    }
+
+    fn validate(&self, module: &Self::Module, max_memory_bytes: usize, max_exports: usize, enable_memory64: bool) -> Result<(), WasmRuntimeError> {
+        for import in module.imports() {
+            if let ExternType::Memory(memory_type) = import.ty() {
+                Self::check_memory_limits(&memory_type, max_memory_bytes, enable_memory64)?;
+            }
+            let resolvable = SUPPORTED_IMPORTS
+                .iter()
+                .any(|&(m, n)| m == import.module() && n == import.name());
+            if !resolvable {
+                return Err(WasmRuntimeError::ValidationError(format!(
+                    "unresolvable import '{}.{}': no matching host function",
+                    import.module(),
+                    import.name()
+                )));
+            }
+        }
+
+        let mut exported_functions = 0usize;
+        for export in module.exports() {
+            match export.ty() {
+                ExternType::Func(_) => exported_functions += 1,
+                ExternType::Memory(memory_type) => Self::check_memory_limits(&memory_type, max_memory_bytes, enable_memory64)?,
+                _ => {}
+            }
+        }
+        if exported_functions > max_exports {
+            return Err(WasmRuntimeError::ValidationError(format!(
+                "module exports {exported_functions} functions, exceeding the configured cap of {max_exports}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn instantiate(&self, module: &Self::Module, max_memory_bytes: usize) -> Result<Self::Instance, WasmRuntimeError> {
+        let mut store = Store::new(&self.engine, HostState::new(max_memory_bytes));
+
+        let mut linker = Linker::new();
+        self.add_host_functions(&mut linker)?;
+
+        let instance = linker
+            .instantiate(&mut store, module)
+            .map_err(|e| WasmRuntimeError::InstanceCreationError(format!("Failed to instantiate: {}", e)))?
+            .start(&mut store)
+            .map_err(|e| WasmRuntimeError::InstanceCreationError(format!("Failed to start: {}", e)))?;
+
+        let memory = instance.get_memory(&store, "memory").ok();
+        let initial_memory_pages = memory.as_ref().map(|m| m.size(&store)).unwrap_or(0);
+
+        let mut functions = BTreeMap::new();
+        let mut nullary_functions = BTreeMap::new();
+        for export in module.exports() {
+            if let ExternType::Func(func_type) = export.ty() {
+                if let Some(func) = instance.get_func(&store, export.name()) {
+                    if func_type.params().is_empty() && func_type.results().is_empty() {
+                        if let Ok(typed_func) = func.typed::<(), ()>(&store) {
+                            nullary_functions.insert(export.name().to_string(), typed_func);
+                        }
+                    }
+                    functions.insert(export.name().to_string(), (func, func_type.clone()));
+                }
+            }
+        }
+
+        Ok(WasmiInstance {
+            instance,
+            store,
+            memory,
+            functions,
+            nullary_functions,
+            initial_memory_pages,
+        })
+    }
+
+    fn call(
+        &self,
+        instance: &mut Self::Instance,
+        function_name: &str,
+        args: &[Value],
+        fuel_budget: u64,
+    ) -> Result<CallOutcome, WasmRuntimeError> {
+        let (func, func_type) = instance.functions.get(function_name)
+            .ok_or_else(|| WasmRuntimeError::FunctionNotFound(function_name.to_string()))?
+            .clone();
+
+        if args.len() != func_type.params().len() {
+            return Err(WasmRuntimeError::TypeMismatch);
+        }
+        for (arg, expected) in args.iter().zip(func_type.params()) {
+            if Self::value_type_of(arg) != *expected {
+                return Err(WasmRuntimeError::TypeMismatch);
+            }
+        }
+
+        instance.store.set_fuel(fuel_budget)
+            .map_err(|e| WasmRuntimeError::ExecutionError(format!("Failed to set fuel budget: {}", e)))?;
+
+        let mut results: Vec<Value> = func_type.results().iter().map(Self::default_value_for).collect();
+
+        let call_result = func.call(&mut instance.store, args, &mut results);
+
+        let remaining_fuel = instance.store.get_fuel().unwrap_or(0);
+        let fuel_consumed = fuel_budget.saturating_sub(remaining_fuel);
+
+        call_result.map_err(|e| {
+            if remaining_fuel == 0 {
+                WasmRuntimeError::ExecutionError("fuel exhausted / timeout".to_string())
+            } else {
+                WasmRuntimeError::ExecutionError(format!("Function execution failed: {}", e))
+            }
+        })?;
+
+        Ok(CallOutcome { results, fuel_consumed })
+    }
+
+    fn reset_for_reuse(&self, instance: &mut Self::Instance) {
+        if let Some(memory) = instance.memory {
+            memory.data_mut(&mut instance.store).fill(0);
+        }
+        instance.store.data_mut().reset();
+    }
+
+    fn memory_usage_bytes(&self, instance: &Self::Instance) -> usize {
+        instance.memory.as_ref()
+            .map(|memory| memory.size(&instance.store) as usize * 65536)
+            .unwrap_or(0)
+    }
+
+    fn memory_info(&self, instance: &Self::Instance) -> Option<MemoryInfo> {
+        instance.memory.as_ref().map(|memory| {
+            let total_pages = memory.size(&instance.store) as u64;
+            let max_pages = instance.store.data().max_memory_bytes() as u64 / WASM_PAGE_SIZE_BYTES;
+            MemoryInfo {
+                total_pages,
+                used_pages: total_pages, // Simplified
+                max_pages: Some(max_pages),
+            }
+        })
+    }
+}
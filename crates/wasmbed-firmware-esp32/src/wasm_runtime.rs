@@ -6,15 +6,11 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
-use log::{error, info, warn};
-use wasmi::{
-    Engine, Module, Linker, Store, Instance, Memory,
-    TypedFunc, Caller, ExternType, FuncType, Value
-};
+use log::info;
+use wasmi::Value;
 use thiserror::Error;
 
-use wasmbed_protocol::{ClientMessage, ServerMessage, DeviceUuid};
-use wasmbed_types::PublicKey;
+use crate::wasm_backend::{WasmBackend, WasmBackendKind, WasmiBackend};
 
 /// Configuration for WASM runtime
 #[derive(Debug, Clone)]
@@ -27,6 +23,25 @@ pub struct WasmRuntimeConfig {
     pub default_timeout: Duration,
     /// Maximum stack size per application
     pub max_stack_size: usize,
+    /// Fuel units charged per second of `default_timeout`, used to derive a
+    /// deterministic instruction budget for each call since wall-clock
+    /// timers aren't reliable scheduling primitives on the ESP32.
+    pub fuel_per_second: u64,
+    /// Ceiling used to normalize `cpu_usage` into a 0-100 scale: an
+    /// application whose calls have consumed this many fuel units in total
+    /// reports 100% CPU usage.
+    pub fuel_cost_ceiling: u64,
+    /// Which `WasmBackend` kind this configuration names. `WasmRuntime::new`
+    /// does not read it: the backend that executes modules is the
+    /// `WasmRuntime` type parameter `B`.
+    pub backend: WasmBackendKind,
+    /// Maximum number of exported functions a module may declare; rejected
+    /// at `load_module` time rather than discovered by probing later.
+    pub max_exports: usize,
+    /// Enables the memory64 proposal on the engine, widening linear memory
+    /// to 64-bit addressing. Defaults to disabled; a module that declares a
+    /// 64-bit memory while this is off is rejected at validation time.
+    pub enable_memory64: bool,
 }
 
 impl Default for WasmRuntimeConfig {
@@ -36,33 +51,63 @@ impl Default for WasmRuntimeConfig {
             max_concurrent_apps: 4,
             default_timeout: Duration::from_secs(30),
             max_stack_size: 64 * 1024, // 64KB stack
+            fuel_per_second: 1_000_000,
+            fuel_cost_ceiling: 10_000_000,
+            backend: WasmBackendKind::default(),
+            max_exports: 64,
+            enable_memory64: false,
         }
     }
 }
 
-/// WASM Runtime for ESP32 devices using wasmi 0.17
-pub struct WasmRuntime {
-    /// Engine for WASM execution
-    engine: Engine,
+/// WASM Runtime for ESP32 devices, generic over the `WasmBackend` that
+/// actually executes modules. Defaults to `WasmiBackend`; a second
+/// register-machine interpreter can be plugged in behind a cargo feature
+/// without touching `execute_function`, `get_memory_info`, or the metrics
+/// paths below.
+pub struct WasmRuntime<B: WasmBackend = WasmiBackend> {
+    /// Execution backend
+    backend: B,
     /// Active WASM instances
-    instances: BTreeMap<String, WasmInstance>,
+    instances: BTreeMap<String, Slot<B::Instance>>,
+    /// Parsed modules, keyed by a content hash of their bytes, so the same
+    /// module can be re-instantiated without re-parsing and re-validating it.
+    compiled_modules: BTreeMap<u64, Arc<B::Module>>,
+    /// Previously-stopped instances kept warm for reuse, bounded by
+    /// `config.max_concurrent_apps`. A `load_module` call for a module whose
+    /// hash matches a pooled instance reuses it instead of instantiating
+    /// from scratch.
+    pool: Vec<Slot<B::Instance>>,
     /// Runtime configuration
     config: WasmRuntimeConfig,
 }
 
-/// WASM Instance wrapper
-#[derive(Debug)]
-pub struct WasmInstance {
-    /// WASM instance
-    instance: Instance,
-    /// Store for the instance
-    store: Store<()>,
+/// A backend instance plus the bookkeeping `WasmRuntime` needs to manage it,
+/// kept backend-agnostic so pooling and metrics don't depend on `B`.
+struct Slot<I> {
+    instance: I,
     /// Module name
     module_name: String,
-    /// Memory reference
-    memory: Option<Memory>,
-    /// Exported functions
-    functions: BTreeMap<String, TypedFunc<(), ()>>,
+    /// Content hash of the module this instance was instantiated from, used
+    /// to match it back up with a compatible pooled slot.
+    module_hash: u64,
+    /// Total fuel consumed across all calls, for `cpu_usage` reporting
+    fuel_consumed_total: u64,
+    /// Number of calls made, for `avg_execution_time`
+    call_count: u64,
+    /// Total wall-clock time spent in calls, in microseconds
+    execution_time_total_micros: u64,
+}
+
+/// Snapshot of the instance-pool and module-cache occupancy, for diagnostics.
+#[derive(Debug, Clone)]
+pub struct PoolStats {
+    /// Number of pre-instantiated, idle instances currently held.
+    pub pooled_instances: usize,
+    /// Maximum number of instances the pool will hold (`max_concurrent_apps`).
+    pub pool_capacity: usize,
+    /// Number of distinct modules whose parse/validation result is cached.
+    pub compiled_modules: usize,
 }
 
 /// WASM Runtime errors
@@ -86,92 +131,131 @@ pub enum WasmRuntimeError {
     InvalidInstruction,
     #[error("Type mismatch")]
     TypeMismatch,
+    #[error("Module validation failed: {0}")]
+    ValidationError(String),
 }
 
-impl WasmRuntime {
+impl<B: WasmBackend> WasmRuntime<B> {
     /// Create a new WASM runtime
     pub fn new(config: WasmRuntimeConfig) -> Result<Self, WasmRuntimeError> {
-        // Create engine with default configuration
-        let engine = Engine::default();
-        
         Ok(Self {
-            engine,
+            backend: B::new(&config),
             instances: BTreeMap::new(),
+            compiled_modules: BTreeMap::new(),
+            pool: Vec::new(),
             config,
         })
     }
 
-    /// Load a WASM module
+    /// Parse and validate `wasm_bytes` ahead of time and cache the result, so
+    /// a later `load_module` call with the same bytes skips compilation.
+    /// Does not instantiate anything or touch the instance pool.
+    pub fn precompile_module(&mut self, module_name: &str, wasm_bytes: &[u8]) -> Result<(), WasmRuntimeError> {
+        self.compile_module(module_name, wasm_bytes)?;
+        Ok(())
+    }
+
+    /// Snapshot of the instance-pool and module-cache occupancy.
+    pub fn pool_stats(&self) -> PoolStats {
+        PoolStats {
+            pooled_instances: self.pool.len(),
+            pool_capacity: self.config.max_concurrent_apps,
+            compiled_modules: self.compiled_modules.len(),
+        }
+    }
+
+    /// Compile `wasm_bytes` if it isn't already in `compiled_modules`, and
+    /// return its content hash alongside the cached module.
+    fn compile_module(&mut self, module_name: &str, wasm_bytes: &[u8]) -> Result<(u64, Arc<B::Module>), WasmRuntimeError> {
+        let hash = Self::hash_module_bytes(wasm_bytes);
+
+        if let Some(module) = self.compiled_modules.get(&hash) {
+            return Ok((hash, module.clone()));
+        }
+
+        info!("Compiling WASM module '{}' (cache miss)", module_name);
+        let module = Arc::new(self.backend.compile(wasm_bytes)?);
+        self.compiled_modules.insert(hash, module.clone());
+        Ok((hash, module))
+    }
+
+    fn hash_module_bytes(wasm_bytes: &[u8]) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        wasm_bytes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Load a WASM module, reusing a pooled instance of the same module
+    /// bytes if one is idle, or instantiating fresh otherwise.
     pub fn load_module(&mut self, module_name: &str, wasm_bytes: &[u8]) -> Result<(), WasmRuntimeError> {
         info!("Loading WASM module: {}", module_name);
 
-        // Parse the WASM module
-        let module = Module::new(&self.engine, wasm_bytes)
-            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to parse module: {}", e)))?;
-
-        // Create a new store
-        let mut store = Store::new(&self.engine, ());
-
-        // Create linker for host functions
-        let mut linker = Linker::new();
-        
-        // Add host functions
-        self.add_host_functions(&mut linker)?;
-
-        // Instantiate the module
-        let instance = linker
-            .instantiate(&mut store, &module)
-            .map_err(|e| WasmRuntimeError::InstanceCreationError(format!("Failed to instantiate: {}", e)))?
-            .start(&mut store)
-            .map_err(|e| WasmRuntimeError::InstanceCreationError(format!("Failed to start: {}", e)))?;
-
-        // Get memory if available
-        let memory = instance.get_memory(&store, "memory").ok();
-
-        // Extract exported functions
-        let mut functions = BTreeMap::new();
-        for export in module.exports() {
-            if let ExternType::Func(func_type) = export.ty() {
-                if func_type.params().is_empty() && func_type.results().is_empty() {
-                    if let Some(func) = instance.get_func(&store, export.name()) {
-                        if let Ok(typed_func) = TypedFunc::<(), ()>::new(&func, &store) {
-                            functions.insert(export.name().to_string(), typed_func);
-                        }
-                    }
-                }
-            }
+        let (hash, module) = self.compile_module(module_name, wasm_bytes)?;
+        self.backend.validate(&module, self.config.max_memory_per_app, self.config.max_exports, self.config.enable_memory64)?;
+
+        if let Some(pos) = self.pool.iter().position(|slot| slot.module_hash == hash) {
+            let mut slot = self.pool.remove(pos);
+            slot.module_name = module_name.to_string();
+            slot.fuel_consumed_total = 0;
+            slot.call_count = 0;
+            slot.execution_time_total_micros = 0;
+            self.instances.insert(module_name.to_string(), slot);
+            info!("Reused pooled instance for module: {} (pool hit)", module_name);
+            return Ok(());
         }
 
-        // Create WASM instance wrapper
-        let wasm_instance = WasmInstance {
+        let instance = self.backend.instantiate(&module, self.config.max_memory_per_app)?;
+        let slot = Slot {
             instance,
-            store,
             module_name: module_name.to_string(),
-            memory,
-            functions,
+            module_hash: hash,
+            fuel_consumed_total: 0,
+            call_count: 0,
+            execution_time_total_micros: 0,
         };
 
-        // Store the instance
-        self.instances.insert(module_name.to_string(), wasm_instance);
+        self.instances.insert(module_name.to_string(), slot);
 
         info!("WASM module loaded successfully: {}", module_name);
         Ok(())
     }
 
-    /// Execute a function in a WASM module
-    pub fn execute_function(&mut self, module_name: &str, function_name: &str, _args: &[Value]) -> Result<Value, WasmRuntimeError> {
-        let instance = self.instances.get_mut(module_name)
+    /// Stop a running module. If the instance pool has room, the instance is
+    /// reset (see `WasmBackend::reset_for_reuse`) and the slot is kept warm
+    /// for the next `load_module` call with matching module bytes, avoiding
+    /// re-parsing and re-instantiation. Otherwise the instance is dropped.
+    pub fn unload_application(&mut self, module_name: &str) -> Result<(), WasmRuntimeError> {
+        let mut slot = self.instances.remove(module_name)
             .ok_or_else(|| WasmRuntimeError::ApplicationNotFound(module_name.to_string()))?;
 
-        let func = instance.functions.get(function_name)
-            .ok_or_else(|| WasmRuntimeError::FunctionNotFound(function_name.to_string()))?;
+        if self.pool.len() < self.config.max_concurrent_apps {
+            self.backend.reset_for_reuse(&mut slot.instance);
+            self.pool.push(slot);
+        }
+
+        Ok(())
+    }
+
+    /// Execute a function in a WASM module with real arguments, enforcing
+    /// `config.default_timeout` via a deterministic fuel budget rather than
+    /// a wall-clock timer. Validates `args` against the function's actual
+    /// signature before calling it.
+    pub fn execute_function(&mut self, module_name: &str, function_name: &str, args: &[Value]) -> Result<Vec<Value>, WasmRuntimeError> {
+        let fuel_budget = (self.config.fuel_per_second as f64 * self.config.default_timeout.as_secs_f64()) as u64;
+
+        let slot = self.instances.get_mut(module_name)
+            .ok_or_else(|| WasmRuntimeError::ApplicationNotFound(module_name.to_string()))?;
+
+        let started = std::time::Instant::now();
+        let outcome = self.backend.call(&mut slot.instance, function_name, args, fuel_budget)?;
+        let elapsed = started.elapsed();
 
-        // Execute the function
-        func.call(&mut instance.store, ())
-            .map_err(|e| WasmRuntimeError::ExecutionError(format!("Function execution failed: {}", e)))?;
+        slot.fuel_consumed_total += outcome.fuel_consumed;
+        slot.call_count += 1;
+        slot.execution_time_total_micros += elapsed.as_micros() as u64;
 
-        // Return a dummy value for now
-        Ok(Value::I32(0))
+        Ok(outcome.results)
     }
 
     /// Get application status
@@ -185,88 +269,33 @@ impl WasmRuntime {
 
     /// Get application metrics
     pub fn get_application_metrics(&self, module_name: &str) -> Option<ApplicationMetrics> {
-        if let Some(instance) = self.instances.get(module_name) {
-            let memory_usage = if let Some(memory) = &instance.memory {
-                memory.size(&instance.store) as usize * 65536 // Convert pages to bytes
-            } else {
-                0
-            };
+        let slot = self.instances.get(module_name)?;
 
-            Some(ApplicationMetrics {
-                app_id: module_name.to_string(),
-                memory_usage,
-                cpu_usage: 0, // TODO: Implement actual CPU tracking
-                function_calls: 0, // TODO: Implement function call counting
-                avg_execution_time: 0, // TODO: Implement execution time tracking
-                error_count: 0, // TODO: Implement error counting
-                status: ApplicationStatus::Running,
-                last_activity: SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs(),
-            })
+        let memory_usage = self.backend.memory_usage_bytes(&slot.instance);
+        let cpu_usage = ((slot.fuel_consumed_total as f64 / self.config.fuel_cost_ceiling as f64) * 100.0)
+            .min(100.0) as u8;
+        let avg_execution_time = if slot.call_count > 0 {
+            (slot.execution_time_total_micros / slot.call_count) as u32
         } else {
-            None
-        }
+            0
+        };
+
+        Some(ApplicationMetrics {
+            app_id: module_name.to_string(),
+            memory_usage,
+            cpu_usage,
+            function_calls: slot.call_count,
+            avg_execution_time,
+            error_count: 0, // TODO: Implement error counting
+            status: ApplicationStatus::Running,
+            last_activity: SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs(),
+        })
     }
 
     /// Get memory info for an application
     pub fn get_memory_info(&self, module_name: &str) -> Option<MemoryInfo> {
-        if let Some(instance) = self.instances.get(module_name) {
-            if let Some(memory) = &instance.memory {
-                Some(MemoryInfo {
-                    total_pages: memory.size(&instance.store),
-                    used_pages: memory.size(&instance.store), // Simplified
-                    max_pages: Some(16), // 1MB max
-                })
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    }
-
-    /// Add host functions to the linker
-    fn add_host_functions(&self, linker: &mut Linker<()>) -> Result<(), WasmRuntimeError> {
-        // Add console.log function
-        linker
-            .func_wrap("console", "log", |caller: Caller<'_, ()>, ptr: i32, len: i32| {
-                if let Some(memory) = caller.get_export("memory") {
-                    if let Ok(bytes) = memory.read(&caller, ptr as u32, len as u32) {
-                        let message = String::from_utf8_lossy(&bytes);
-                        info!("WASM console.log: {}", message);
-                    }
-                }
-            })
-            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add console.log: {}", e)))?;
-
-        // Add memory allocation function
-        linker
-            .func_wrap("env", "malloc", |caller: Caller<'_, ()>, size: i32| -> i32 {
-                // Simplified memory allocation - return a dummy pointer
-                size
-            })
-            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add malloc: {}", e)))?;
-
-        // Add memory free function
-        linker
-            .func_wrap("env", "free", |caller: Caller<'_, ()>, ptr: i32| {
-                // Simplified memory free - do nothing for now
-                let _ = caller;
-                let _ = ptr;
-            })
-            .map_err(|e| WasmRuntimeError::ModuleLoadError(format!("Failed to add free: {}", e)))?;
-
-        Ok(())
-    }
-
-    /// Create a new engine with custom configuration
-    pub fn create_engine() -> Engine {
-        let mut engine_config = wasmi::Config::default();
-        // Enable bulk memory operations
-        engine_config.wasm_bulk_memory(true);
-        // Enable reference types
-        engine_config.wasm_reference_types(true);
-        
-        Engine::new(&engine_config)
+        let slot = self.instances.get(module_name)?;
+        self.backend.memory_info(&slot.instance)
     }
 }
 
@@ -292,12 +321,13 @@ pub struct ApplicationMetrics {
     pub last_activity: u64,
 }
 
-/// Memory information
+/// Memory information. Page counts are `u64` so a memory64 instance's
+/// address space isn't truncated.
 #[derive(Debug, Clone)]
 pub struct MemoryInfo {
-    pub total_pages: u32,
-    pub used_pages: u32,
-    pub max_pages: Option<u32>,
+    pub total_pages: u64,
+    pub used_pages: u64,
+    pub max_pages: Option<u64>,
 }
 
 /// Simple WASM modules for testing
@@ -330,18 +360,18 @@ mod tests {
     #[test]
     fn test_runtime_creation() {
         let config = WasmRuntimeConfig::default();
-        let runtime = WasmRuntime::new(config);
+        let runtime = WasmRuntime::<WasmiBackend>::new(config);
         assert!(runtime.is_ok());
     }
 
     #[test]
     fn test_simple_module_loading() {
         let config = WasmRuntimeConfig::default();
-        let mut runtime = WasmRuntime::new(config).unwrap();
-        
+        let mut runtime = WasmRuntime::<WasmiBackend>::new(config).unwrap();
+
         let result = runtime.load_module("test", test_modules::SIMPLE_WASM);
         assert!(result.is_ok());
-        
+
         let status = runtime.get_application_status("test");
         assert_eq!(status, Some(ApplicationStatus::Running));
     }
@@ -349,10 +379,10 @@ mod tests {
     #[test]
     fn test_function_execution() {
         let config = WasmRuntimeConfig::default();
-        let mut runtime = WasmRuntime::new(config).unwrap();
-        
+        let mut runtime = WasmRuntime::<WasmiBackend>::new(config).unwrap();
+
         runtime.load_module("test", test_modules::SIMPLE_WASM).unwrap();
-        
+
         let result = runtime.execute_function("test", "main", &[]);
         assert!(result.is_ok());
     }
@@ -360,10 +390,10 @@ mod tests {
     #[test]
     fn test_memory_info() {
         let config = WasmRuntimeConfig::default();
-        let mut runtime = WasmRuntime::new(config).unwrap();
-        
+        let mut runtime = WasmRuntime::<WasmiBackend>::new(config).unwrap();
+
         runtime.load_module("test", test_modules::SIMPLE_WASM).unwrap();
-        
+
         let memory_info = runtime.get_memory_info("test");
         // Memory info might be None for simple modules without memory
         // This is expected behavior
@@ -372,16 +402,56 @@ mod tests {
     #[test]
     fn test_metrics() {
         let config = WasmRuntimeConfig::default();
-        let mut runtime = WasmRuntime::new(config).unwrap();
-        
+        let mut runtime = WasmRuntime::<WasmiBackend>::new(config).unwrap();
+
         runtime.load_module("test", test_modules::SIMPLE_WASM).unwrap();
-        
+
         let metrics = runtime.get_application_metrics("test");
         assert!(metrics.is_some());
-        
+
         if let Some(metrics) = metrics {
             assert_eq!(metrics.app_id, "test");
             assert_eq!(metrics.status, ApplicationStatus::Running);
         }
     }
-}
\ No newline at end of file
+
+    /// Conformance fuzz test: no `arbitrary`/`proptest`-style dependency is
+    /// available to this crate, so this is a self-contained substitute — a
+    /// tiny xorshift PRNG flips a handful of bytes in the bundled test
+    /// modules and feeds the result through `load_module`/`execute_function`.
+    /// Whatever comes out, malformed or not, must surface as a `Result`
+    /// rather than a panic.
+    #[test]
+    fn test_fuzz_load_and_execute_never_panics() {
+        let mut state: u64 = 0x9e3779b97f4a7c15;
+        let mut next_u64 = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for i in 0..200u32 {
+            let mut bytes = if i % 2 == 0 {
+                test_modules::SIMPLE_WASM.to_vec()
+            } else {
+                test_modules::MULTIPLY_WASM.to_vec()
+            };
+
+            let flips = (next_u64() % 3) + 1;
+            for _ in 0..flips {
+                let idx = (next_u64() as usize) % bytes.len();
+                bytes[idx] ^= (next_u64() & 0xff) as u8;
+            }
+
+            let config = WasmRuntimeConfig::default();
+            let mut runtime = WasmRuntime::<WasmiBackend>::new(config).unwrap();
+            let module_name = format!("fuzz-{i}");
+
+            if runtime.load_module(&module_name, &bytes).is_ok() {
+                let _ = runtime.execute_function(&module_name, "main", &[]);
+                let _ = runtime.execute_function(&module_name, "multiply", &[Value::I32(2), Value::I32(3)]);
+            }
+        }
+    }
+}
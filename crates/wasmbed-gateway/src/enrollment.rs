@@ -1,18 +1,47 @@
 // SPDX-License-Identifier: AGPL-3.0
 // Copyright © 2025 Wasmbed contributors
 
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use tracing::{info, warn};
 
+/// How long an issued challenge nonce remains valid; a device that doesn't
+/// answer within this window must request a fresh one.
+const CHALLENGE_TTL: Duration = Duration::from_secs(30);
+
+/// Identity the mTLS layer verified for the connection attempting
+/// enrollment: the subject CN of the client certificate (chained to the
+/// pinned client CA) and the public key extracted from it.
+pub struct TlsPeerIdentity {
+    pub subject_cn: String,
+    pub public_key: String,
+}
+
+/// A nonce issued to `device_id`, outstanding until it's answered or expires.
+struct PendingChallenge {
+    nonce: [u8; 32],
+    issued_at: SystemTime,
+}
+
 /// Enrollment Service for device registration
 pub struct EnrollmentService {
     pub pairing_enabled: bool,
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+    /// The Ed25519 public key each device first enrolled with, trusted on
+    /// first use; later enrollments for the same `device_id` must sign with
+    /// this same key.
+    known_keys: Mutex<HashMap<String, Vec<u8>>>,
 }
 
 impl EnrollmentService {
     pub fn new() -> Self {
         Self {
             pairing_enabled: false,
+            challenges: Mutex::new(HashMap::new()),
+            known_keys: Mutex::new(HashMap::new()),
         }
     }
 
@@ -26,16 +55,95 @@ impl EnrollmentService {
         info!("Pairing mode disabled");
     }
 
-    pub async fn enroll_device(&self, device_id: String, public_key: String) -> Result<String, String> {
-        if !self.pairing_enabled {
-            return Err("Pairing mode is disabled".to_string());
+    /// Issue a fresh random nonce for `device_id` to sign, replacing any
+    /// challenge already outstanding for it.
+    pub fn issue_challenge(&self, device_id: &str) -> [u8; 32] {
+        let nonce: [u8; 32] = rand::random();
+        self.challenges.lock().unwrap().insert(
+            device_id.to_string(),
+            PendingChallenge { nonce, issued_at: SystemTime::now() },
+        );
+        nonce
+    }
+
+    /// Enroll `device_id` after verifying an Ed25519 signature over
+    /// `nonce || device_id` against `public_key`, proving the device
+    /// controls the private key it claims rather than trusting a bare
+    /// assertion. `nonce` must match the outstanding challenge issued via
+    /// [`Self::issue_challenge`] and not have expired; it is consumed on use
+    /// so a captured attestation can't be replayed. The first key ever seen
+    /// for a `device_id` is trusted (trust-on-first-use); any later
+    /// enrollment attempt under the same `device_id` with a different key is
+    /// rejected. When `tls_identity` is present, the enrollment is also
+    /// rejected unless it matches `device_id`/`public_key`, so presenting a
+    /// valid client certificate for one device can't be used to enroll under
+    /// a different device's identity.
+    pub async fn enroll_device(
+        &self,
+        device_id: String,
+        public_key: Vec<u8>,
+        nonce: [u8; 32],
+        signature: Vec<u8>,
+        tls_identity: Option<&TlsPeerIdentity>,
+    ) -> Result<String, String> {
+        let pending = self.challenges.lock().unwrap().remove(&device_id);
+        let pending = pending.ok_or_else(|| {
+            format!("No outstanding challenge for device {}", device_id)
+        })?;
+
+        if pending.nonce != nonce {
+            return Err("Nonce does not match the outstanding challenge".to_string());
         }
+        if pending.issued_at.elapsed().unwrap_or(Duration::MAX) > CHALLENGE_TTL {
+            return Err("Challenge has expired".to_string());
+        }
+
+        let public_key_bytes: [u8; 32] = public_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid Ed25519 public key length".to_string())?;
+        let verifying_key = VerifyingKey::from_bytes(&public_key_bytes)
+            .map_err(|e| format!("Invalid Ed25519 public key: {}", e))?;
+
+        let signature_bytes: [u8; 64] = signature
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid Ed25519 signature length".to_string())?;
+        let signature = Signature::from_bytes(&signature_bytes);
+
+        let mut signed_message = pending.nonce.to_vec();
+        signed_message.extend_from_slice(device_id.as_bytes());
+        verifying_key
+            .verify(&signed_message, &signature)
+            .map_err(|_| "Signature verification failed: device does not control the claimed key".to_string())?;
+
+        if let Some(identity) = tls_identity {
+            let public_key_b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &public_key);
+            if identity.subject_cn != device_id || identity.public_key != public_key_b64 {
+                warn!(
+                    "Rejecting enrollment for {}: TLS-presented identity ({}, {}) does not match requested identity",
+                    device_id, identity.subject_cn, identity.public_key
+                );
+                return Err("TLS-presented identity does not match enrollment request".to_string());
+            }
+        }
+
+        {
+            let mut known_keys = self.known_keys.lock().unwrap();
+            match known_keys.get(&device_id) {
+                Some(trusted_key) if trusted_key != &public_key => {
+                    warn!("Rejecting enrollment for {}: key does not match the key trusted on first use", device_id);
+                    return Err("Device key does not match previously trusted key".to_string());
+                },
+                Some(_) => {},
+                None => {
+                    known_keys.insert(device_id.clone(), public_key.clone());
+                },
+            }
+        }
+
+        info!("Enrolling device: {} (Ed25519 signature verified)", device_id);
 
-        info!("Enrolling device: {}", device_id);
-        
-        // Simulate enrollment process
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        
         Ok(format!("device-uuid-{}", device_id))
     }
 }
@@ -8,15 +8,16 @@ use std::net::SocketAddr;
 
 use anyhow::Result;
 use axum::{
+    extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
     extract::{Path, State},
     http::StatusCode,
-    response::Json,
+    response::{IntoResponse, Json},
     routing::{get, post, put, delete},
     Router,
 };
 use kube::Api;
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, warn};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -40,6 +41,17 @@ pub struct HttpApiServer {
     pub pairing_mode: Arc<RwLock<bool>>,
     pub pairing_timeout_seconds: Arc<RwLock<u64>>,
     pub heartbeat_timeout_seconds: Arc<RwLock<u64>>,
+    /// This gateway's registered `GatewayCredential::key`, checked against
+    /// the `Authorization: Bearer` header on the controller-facing
+    /// deploy/prepare/commit/abort/stop/status routes. `None` (the default)
+    /// leaves those routes open, so a gateway that hasn't been given a key
+    /// yet - or the older `wasmbed-application-controller` binary, which
+    /// sends no credential at all - keeps working unchanged.
+    pub controller_key: Arc<RwLock<Option<String>>>,
+    /// Gateway-pushed events for controllers subscribed over
+    /// `/api/v1/controller/events`; see `wasmbed-k8s-controller`'s
+    /// `gateway_connection::GatewayEvent`, which this JSON shape mirrors.
+    pub controller_events: broadcast::Sender<String>,
 }
 
 /// Active device connection information with TLS support
@@ -146,17 +158,28 @@ impl HttpApiServer {
             tls_config: Arc::new(TlsServer::new(
                 "0.0.0.0:8443".parse().unwrap(),
                 rustls_pki_types::CertificateDer::from(vec![]),
-                rustls_pki_types::PrivatePkcs8KeyDer::from(vec![]),
+                rustls_pki_types::PrivateKeyDer::Pkcs8(rustls_pki_types::PrivatePkcs8KeyDer::from(vec![])),
                 rustls_pki_types::CertificateDer::from(vec![]),
             )),
             cbor_tls_listener: None,
             pairing_mode: Arc::new(RwLock::new(false)),
             pairing_timeout_seconds: Arc::new(RwLock::new(300)),
             heartbeat_timeout_seconds: Arc::new(RwLock::new(90)),
+            controller_key: Arc::new(RwLock::new(None)),
+            controller_events: broadcast::channel(256).0,
         })
     }
-    
-    
+
+    /// Verify a controller-facing request's `Authorization: Bearer <key>`
+    /// header against `controller_key`, when one is configured. Unconfigured
+    /// (the default) means open, matching behavior before this check
+    /// existed; this only starts rejecting once a gateway is actually given
+    /// a key to check against.
+    async fn authorize_controller(&self, headers: &axum::http::HeaderMap) -> Result<(), StatusCode> {
+        let configured = self.controller_key.read().await;
+        check_bearer_auth(configured.as_deref(), headers)
+    }
+
     /// Start CBOR/TLS listener for device connections
     pub async fn start_cbor_tls_listener(&mut self, bind_addr: SocketAddr) -> Result<()> {
         info!("Starting CBOR/TLS listener on {}", bind_addr);
@@ -254,6 +277,9 @@ impl HttpApiServer {
             .route("/api/v1/devices/:device_id/enroll", post(enroll_device))
             .route("/api/v1/devices/:device_id/connect", post(connect_device))
             .route("/api/v1/devices/:device_id/deploy", post(deploy_application))
+            .route("/api/v1/devices/:device_id/prepare", post(prepare_application))
+            .route("/api/v1/devices/:device_id/commit/:app_id", post(commit_application))
+            .route("/api/v1/devices/:device_id/abort/:app_id", post(abort_application))
             .route("/api/v1/devices/:device_id/stop/:app_id", post(stop_application))
             .route("/api/v1/devices/:device_id/status/:app_id", get(get_application_status))
             .route("/api/v1/devices/:device_id/applications", get(get_device_applications))
@@ -280,6 +306,7 @@ impl HttpApiServer {
             .route("/api/v1/metrics/system", get(get_system_metrics))
             .route("/api/v1/alerts", get(get_alerts))
             .route("/api/v1/drone/command", post(send_drone_command))
+            .route("/api/v1/controller/events", get(controller_events_ws))
             .route("/health", get(health_check))
             .route("/ready", get(readiness_check))
             .with_state(state)
@@ -299,7 +326,14 @@ impl HttpApiServer {
         };
 
         let mut connections = self.device_connections.write().await;
-        connections.insert(device_id, connection);
+        connections.insert(device_id.clone(), connection);
+        drop(connections);
+
+        let _ = self.controller_events.send(serde_json::json!({
+            "type": "device_connected",
+            "device_name": device_id,
+        }).to_string());
+
         info!("Device registered for HTTP API");
     }
 
@@ -334,8 +368,19 @@ impl HttpApiServer {
     pub async fn update_application_status(&self, app_id: &str, status: DeviceApplicationPhase, error: Option<String>) {
         let mut applications = self.applications.write().await;
         if let Some(application) = applications.get_mut(app_id) {
-            application.status = status;
+            application.status = status.clone();
             application.error = error;
+            let device_name = application.device_id.clone();
+            drop(applications);
+
+            let _ = self.controller_events.send(serde_json::json!({
+                "type": "application_phase_changed",
+                "app_namespace": "wasmbed",
+                "app_name": app_id,
+                "device_name": device_name,
+                "phase": status.as_event_str(),
+            }).to_string());
+
             debug!("Updated application status for {}", app_id);
         }
     }
@@ -395,6 +440,83 @@ impl HttpApiServer {
         }
     }
 
+    /// Stage a WASM module on a device without starting it, as the
+    /// "prepare" phase of a two-phase-commit deployment
+    pub async fn prepare_application_on_device(&self, device_id: &str, app_id: &str, wasm_bytes: &[u8]) -> Result<()> {
+        let connections = self.device_connections.read().await;
+
+        if let Some(_connection) = connections.get(device_id) {
+            let prepare_message = ServerMessage::PrepareApplication {
+                app_id: app_id.to_string(),
+                name: app_id.to_string(),
+                wasm_bytes: wasm_bytes.to_vec(),
+            };
+
+            match self.send_message_to_device(device_id, &prepare_message).await {
+                Ok(_) => {
+                    info!("Successfully sent prepare command for app {} to device {}", app_id, device_id);
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Failed to send prepare command for app {} to device {}: {}", app_id, device_id, e);
+                    Err(e)
+                }
+            }
+        } else {
+            Err(anyhow::anyhow!("Device {} not connected", device_id))
+        }
+    }
+
+    /// Start a previously prepared WASM module, as the "commit" phase of a
+    /// two-phase-commit deployment
+    pub async fn commit_application_on_device(&self, device_id: &str, app_id: &str) -> Result<()> {
+        let connections = self.device_connections.read().await;
+
+        if let Some(_connection) = connections.get(device_id) {
+            let commit_message = ServerMessage::CommitApplication {
+                app_id: app_id.to_string(),
+            };
+
+            match self.send_message_to_device(device_id, &commit_message).await {
+                Ok(_) => {
+                    info!("Successfully sent commit command for app {} to device {}", app_id, device_id);
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Failed to send commit command for app {} to device {}: {}", app_id, device_id, e);
+                    Err(e)
+                }
+            }
+        } else {
+            Err(anyhow::anyhow!("Device {} not connected", device_id))
+        }
+    }
+
+    /// Discard a previously prepared WASM module, as the "abort" phase of a
+    /// two-phase-commit deployment
+    pub async fn abort_application_on_device(&self, device_id: &str, app_id: &str) -> Result<()> {
+        let connections = self.device_connections.read().await;
+
+        if let Some(_connection) = connections.get(device_id) {
+            let abort_message = ServerMessage::AbortApplication {
+                app_id: app_id.to_string(),
+            };
+
+            match self.send_message_to_device(device_id, &abort_message).await {
+                Ok(_) => {
+                    info!("Successfully sent abort command for app {} to device {}", app_id, device_id);
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Failed to send abort command for app {} to device {}: {}", app_id, device_id, e);
+                    Err(e)
+                }
+            }
+        } else {
+            Err(anyhow::anyhow!("Device {} not connected", device_id))
+        }
+    }
+
     /// Send message to a specific device via TLS
     async fn send_message_to_device(&self, device_id: &str, message: &ServerMessage) -> Result<()> {
         info!("Sending message to device {}: {:?}", device_id, message);
@@ -461,8 +583,10 @@ async fn get_devices(
 async fn deploy_application(
     State(server): State<Arc<HttpApiServer>>,
     Path(device_id): Path<String>,
+    headers: axum::http::HeaderMap,
     Json(payload): Json<DeploymentRequest>,
 ) -> Result<Json<DeploymentResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
     info!("Received deployment request for device {}: app_id={}", device_id, payload.app_id);
 
     // Check if device is connected
@@ -529,7 +653,9 @@ async fn deploy_application(
 async fn stop_application(
     State(server): State<Arc<HttpApiServer>>,
     Path((device_id, app_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
 ) -> Result<Json<DeploymentResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
     info!("Received stop request for device {}: app_id={}", device_id, app_id);
 
     // Check if device is connected
@@ -570,11 +696,204 @@ async fn stop_application(
     }))
 }
 
+/// Stage a WASM module on a device without starting it ("prepare" phase of
+/// a two-phase-commit deployment)
+async fn prepare_application(
+    State(server): State<Arc<HttpApiServer>>,
+    Path(device_id): Path<String>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<DeploymentRequest>,
+) -> Result<Json<DeploymentResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
+    info!("Received prepare request for device {}: app_id={}", device_id, payload.app_id);
+
+    let connections = server.device_connections.read().await;
+    if !connections.contains_key(&device_id) {
+        return Ok(Json(DeploymentResponse {
+            success: false,
+            message: "Device not connected".to_string(),
+            error: Some("Device not found or not connected".to_string()),
+        }));
+    }
+    drop(connections);
+
+    let wasm_bytes = match base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &payload.wasm_bytes) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to decode WASM bytes: {}", e);
+            return Ok(Json(DeploymentResponse {
+                success: false,
+                message: "Invalid WASM bytes".to_string(),
+                error: Some(format!("Failed to decode base64: {}", e)),
+            }));
+        }
+    };
+
+    let app_id = payload.app_id.clone();
+    server.register_application(
+        app_id.clone(),
+        device_id.clone(),
+        payload.name,
+        wasm_bytes.clone(),
+        None,
+    ).await;
+    server.update_application_status(&app_id, DeviceApplicationPhase::Deploying, None).await;
+
+    match server.prepare_application_on_device(&device_id, &app_id, &wasm_bytes).await {
+        Ok(_) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Prepared, None).await;
+            Ok(Json(DeploymentResponse {
+                success: true,
+                message: format!("Application {} prepared", app_id),
+                error: None,
+            }))
+        }
+        Err(e) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Failed, Some(e.to_string())).await;
+            Ok(Json(DeploymentResponse {
+                success: false,
+                message: "Prepare failed".to_string(),
+                error: Some(e.to_string()),
+            }))
+        }
+    }
+}
+
+/// Start a previously prepared WASM module ("commit" phase of a
+/// two-phase-commit deployment)
+async fn commit_application(
+    State(server): State<Arc<HttpApiServer>>,
+    Path((device_id, app_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<DeploymentResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
+    info!("Received commit request for device {}: app_id={}", device_id, app_id);
+
+    let connections = server.device_connections.read().await;
+    if !connections.contains_key(&device_id) {
+        return Ok(Json(DeploymentResponse {
+            success: false,
+            message: "Device not connected".to_string(),
+            error: Some("Device not found or not connected".to_string()),
+        }));
+    }
+    drop(connections);
+
+    match server.commit_application_on_device(&device_id, &app_id).await {
+        Ok(_) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Running, None).await;
+            Ok(Json(DeploymentResponse {
+                success: true,
+                message: format!("Application {} committed", app_id),
+                error: None,
+            }))
+        }
+        Err(e) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Failed, Some(e.to_string())).await;
+            Ok(Json(DeploymentResponse {
+                success: false,
+                message: "Commit failed".to_string(),
+                error: Some(e.to_string()),
+            }))
+        }
+    }
+}
+
+/// Discard a previously prepared WASM module ("abort" phase of a
+/// two-phase-commit deployment)
+async fn abort_application(
+    State(server): State<Arc<HttpApiServer>>,
+    Path((device_id, app_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<DeploymentResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
+    info!("Received abort request for device {}: app_id={}", device_id, app_id);
+
+    let connections = server.device_connections.read().await;
+    if !connections.contains_key(&device_id) {
+        return Ok(Json(DeploymentResponse {
+            success: false,
+            message: "Device not connected".to_string(),
+            error: Some("Device not found or not connected".to_string()),
+        }));
+    }
+    drop(connections);
+
+    match server.abort_application_on_device(&device_id, &app_id).await {
+        Ok(_) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Aborted, None).await;
+            Ok(Json(DeploymentResponse {
+                success: true,
+                message: format!("Application {} aborted", app_id),
+                error: None,
+            }))
+        }
+        Err(e) => {
+            server.update_application_status(&app_id, DeviceApplicationPhase::Failed, Some(e.to_string())).await;
+            Ok(Json(DeploymentResponse {
+                success: false,
+                message: "Abort failed".to_string(),
+                error: Some(e.to_string()),
+            }))
+        }
+    }
+}
+
+/// Accept a persistent WebSocket connection from the k8s controller and
+/// stream it `controller_events` (device connect/disconnect, application
+/// phase changes), matching the wire format `GatewayConnection` expects:
+/// a JSON "identify" message on connect, "heartbeat"/"heartbeat_ack" text
+/// keepalives, and JSON event payloads in between.
+async fn controller_events_ws(
+    State(server): State<Arc<HttpApiServer>>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_controller_events_ws(socket, server))
+}
+
+async fn handle_controller_events_ws(mut socket: WebSocket, server: Arc<HttpApiServer>) {
+    let mut events = server.controller_events.subscribe();
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(WsMessage::Text(text))) if text == "heartbeat" => {
+                        if socket.send(WsMessage::Text("heartbeat_ack".to_string())).await.is_err() {
+                            return;
+                        }
+                    },
+                    Some(Ok(WsMessage::Text(_))) => {},
+                    Some(Ok(WsMessage::Close(_))) | None => return,
+                    Some(Ok(_)) => {},
+                    Some(Err(e)) => {
+                        warn!("Controller websocket error: {}", e);
+                        return;
+                    },
+                }
+            },
+            event = events.recv() => {
+                match event {
+                    Ok(payload) => {
+                        if socket.send(WsMessage::Text(payload)).await.is_err() {
+                            return;
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        }
+    }
+}
+
 /// Get application status
 async fn get_application_status(
     State(server): State<Arc<HttpApiServer>>,
     Path((_device_id, app_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
 ) -> Result<Json<ApplicationStatusResponse>, StatusCode> {
+    server.authorize_controller(&headers).await?;
     let applications = server.applications.read().await;
     if let Some(app) = applications.get(&app_id) {
         Ok(Json(ApplicationStatusResponse {
@@ -1516,7 +1835,7 @@ async fn connect_device(
     Path(device_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     info!("Connecting device: {}", device_id);
-    
+
     // For now, just return success
     // In the future, this should trigger the connection workflow
     Ok(Json(serde_json::json!({
@@ -1524,3 +1843,60 @@ async fn connect_device(
         "message": format!("Device '{}' connected successfully", device_id)
     })))
 }
+
+/// Pure bearer-token check backing `HttpApiServer::authorize_controller`,
+/// factored out so it's testable without a live `kube::Client`.
+fn check_bearer_auth(configured: Option<&str>, headers: &axum::http::HeaderMap) -> Result<(), StatusCode> {
+    let Some(expected) = configured else {
+        return Ok(());
+    };
+
+    let provided = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if provided == Some(expected) {
+        Ok(())
+    } else {
+        warn!("Rejecting controller request: missing or mismatched bearer token");
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with_bearer(token: &str) -> axum::http::HeaderMap {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn unconfigured_key_accepts_any_request() {
+        assert!(check_bearer_auth(None, &axum::http::HeaderMap::new()).is_ok());
+        assert!(check_bearer_auth(None, &headers_with_bearer("whatever")).is_ok());
+    }
+
+    #[test]
+    fn configured_key_accepts_matching_bearer_token() {
+        assert!(check_bearer_auth(Some("secret"), &headers_with_bearer("secret")).is_ok());
+    }
+
+    #[test]
+    fn configured_key_rejects_missing_header() {
+        let result = check_bearer_auth(Some("secret"), &axum::http::HeaderMap::new());
+        assert_eq!(result, Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn configured_key_rejects_mismatched_token() {
+        let result = check_bearer_auth(Some("secret"), &headers_with_bearer("wrong"));
+        assert_eq!(result, Err(StatusCode::UNAUTHORIZED));
+    }
+}
@@ -16,7 +16,7 @@ use base64;
 
 use wasmbed_k8s_resource::{Device, DeviceStatusUpdate, Application, DevicePhase, ApplicationPhase};
 use wasmbed_protocol::{ClientMessage, ServerMessage, DeviceUuid};
-use wasmbed_tls_utils::{TlsUtils, GatewayServer, GatewayServerConfig, ServerIdentity, AuthorizationResult, MessageContextWithKey, OnClientConnectWithKey, OnClientDisconnectWithKey, OnClientMessageWithKey};
+use wasmbed_tls_utils::{TlsUtils, GatewayServer, GatewayServerConfig, ServerIdentity, AuthorizationResult, MessageContextWithKey, OnClientConnectWithKey, OnClientDisconnectWithKey, OnClientMessageWithKey, CertResolver};
 use wasmbed_types::{GatewayReference, PublicKey};
 
 mod http_api;
@@ -35,6 +35,13 @@ struct Args {
     certificate: PathBuf,
     #[arg(long, env = "WASMBED_GATEWAY_CLIENT_CA")]
     client_ca: PathBuf,
+    /// Directory of `{servername}.pem`/`{servername}.key`/`{servername}.ca.pem`
+    /// triples. When set, the gateway's TLS identity and client CA are
+    /// resolved per-SNI from this directory instead of the static
+    /// `--private-key`/`--certificate`/`--client-ca` above, so one gateway
+    /// can front several tenants.
+    #[arg(long, env = "WASMBED_GATEWAY_TLS_TENANT_DIR")]
+    tls_tenant_dir: Option<PathBuf>,
     #[arg(long, env = "WASMBED_GATEWAY_NAMESPACE")]
     namespace: String,
     #[arg(long, env = "WASMBED_GATEWAY_POD_NAMESPACE")]
@@ -47,6 +54,14 @@ struct Args {
     pairing_timeout_seconds: u64,
     #[arg(long, env = "WASMBED_GATEWAY_HEARTBEAT_TIMEOUT", default_value = "90")]
     heartbeat_timeout_seconds: u64,
+    /// This gateway's credential key, as registered for it in the
+    /// `wasmbed-gateways` ConfigMap `wasmbed-k8s-controller` reads
+    /// (`GatewayCredential::key`). When set, the controller-facing
+    /// deploy/prepare/commit/abort/stop/status routes reject requests
+    /// whose `Authorization: Bearer` token doesn't match it. Left unset,
+    /// those routes accept any caller, matching prior behavior.
+    #[arg(long, env = "WASMBED_GATEWAY_CONTROLLER_KEY")]
+    controller_key: Option<String>,
 }
 
 struct Callbacks {
@@ -258,12 +273,24 @@ impl Callbacks {
                         
                         if tls_public_key_obj != message_public_key {
                             error!("TLS client authentication failed during enrollment: public key mismatch");
-                            let _ = ctx.reply(ServerMessage::EnrollmentRejected { 
-                                reason: "Public key mismatch with TLS certificate".as_bytes().to_vec() 
+                            let _ = ctx.reply(ServerMessage::EnrollmentRejected {
+                                reason: "Public key mismatch with TLS certificate".as_bytes().to_vec()
                             });
                             return;
                         }
-                        
+
+                        // The subject CN is only present when the gateway's
+                        // client cert verifier accepted a certificate chained
+                        // to our pinned client CA; an mTLS connection that
+                        // never presented one can't claim this identity.
+                        if ctx.peer_subject_cn().is_none() {
+                            error!("TLS client authentication failed during enrollment: no client certificate presented");
+                            let _ = ctx.reply(ServerMessage::EnrollmentRejected {
+                                reason: "No client certificate presented".as_bytes().to_vec()
+                            });
+                            return;
+                        }
+
                         info!("TLS client authentication verified during enrollment");
                         
                         // Generate a unique UUID for this device
@@ -593,7 +620,11 @@ async fn main() -> Result<()> {
         let mut heartbeat_timeout = http_server.heartbeat_timeout_seconds.write().await;
         *heartbeat_timeout = args.heartbeat_timeout_seconds;
     }
-    
+    {
+        let mut controller_key = http_server.controller_key.write().await;
+        *controller_key = args.controller_key.clone();
+    }
+
     let http_server = Arc::new(http_server);
 
     let callbacks = Callbacks {
@@ -602,10 +633,23 @@ async fn main() -> Result<()> {
         http_server: http_server.clone(),
     };
 
+    let cert_resolver = match &args.tls_tenant_dir {
+        Some(dir) => {
+            let resolver = Arc::new(
+                CertResolver::from_directory(dir)
+                    .with_context(|| format!("Failed to load TLS tenants from {}", dir.display()))?,
+            );
+            resolver.watch(Duration::from_secs(30));
+            Some(resolver)
+        },
+        None => None,
+    };
+
     let config = GatewayServerConfig {
         bind_addr: args.bind_addr,
         identity,
         client_ca,
+        cert_resolver,
         on_client_connect: Arc::new(callbacks.on_connect()),
         on_client_disconnect: Arc::new(callbacks.on_disconnect()),
         on_client_message: Arc::new(callbacks.on_message()),
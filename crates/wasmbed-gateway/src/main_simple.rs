@@ -4,12 +4,12 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::Parser;
-use tracing::{Level, info, error};
+use tracing::{Level, info};
 use tracing_subscriber::FmtSubscriber;
 
-use wasmbed_tls_utils::{TlsUtils, TlsServer};
+use wasmbed_tls_utils::{CipherSuite, TlsServer, TlsVersion};
 
 #[derive(Parser)]
 #[command(disable_help_subcommand = true)]
@@ -28,6 +28,13 @@ struct Args {
     pod_namespace: String,
     #[arg(long, env = "WASMBED_GATEWAY_POD_NAME")]
     pod_name: String,
+    /// Lowest TLS protocol version to accept. Set to "1.2" to also allow
+    /// constrained RISC-V devices whose TLS stack doesn't support 1.3 yet.
+    #[arg(long, env = "WASMBED_GATEWAY_TLS_MIN_VERSION", default_value = "1.3")]
+    tls_min_version: String,
+    /// Whether connecting devices must present a client certificate.
+    #[arg(long, env = "WASMBED_GATEWAY_TLS_REQUIRE_CLIENT_AUTH", default_value = "true")]
+    tls_require_client_auth: bool,
 }
 
 #[tokio::main]
@@ -39,55 +46,27 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
-    let private_key_bytes =
-        std::fs::read(&args.private_key).with_context(|| {
-            format!(
-                "Failed to read private key from {}",
-                args.private_key.display()
-            )
-        })?;
-    let certificate_bytes =
-        std::fs::read(&args.certificate).with_context(|| {
-            format!(
-                "Failed to read certificate from {}",
-                args.certificate.display()
-            )
-        })?;
-    let client_ca_bytes =
-        std::fs::read(&args.client_ca).with_context(|| {
-            format!(
-                "Failed to read client CA certificate from {}",
-                args.client_ca.display()
-            )
-        })?;
-
-    // Parse PEM certificates using our custom TLS utils
-    let private_key = TlsUtils::parse_private_key(&private_key_bytes)
-        .with_context(|| "Failed to parse private key")?;
-    
-    let certificate = TlsUtils::parse_certificate(&certificate_bytes)
-        .with_context(|| "Failed to parse certificate")?;
-    
-    let client_ca_certs = TlsUtils::parse_certificates(&client_ca_bytes)
-        .with_context(|| "Failed to parse client CA certificates")?;
-
-    let server_key = match private_key {
-        rustls_pki_types::PrivateKeyDer::Pkcs8(pkcs8) => pkcs8,
-        _ => return Err(anyhow::anyhow!("Only PKCS8 private keys are supported")),
+    let min_version = match args.tls_min_version.as_str() {
+        "1.2" => TlsVersion::Tls12,
+        "1.3" => TlsVersion::Tls13,
+        other => return Err(anyhow::anyhow!("Unsupported --tls-min-version {}: expected \"1.2\" or \"1.3\"", other)),
     };
-    
-    let client_ca = client_ca_certs
-        .into_iter()
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("No CA certificate found in PEM file"))?;
 
     // Create custom TLS server
-    let tls_server = TlsServer::new(args.bind_addr, certificate, server_key, client_ca);
-    
+    let tls_server = TlsServer::builder()
+        .bind_addr(args.bind_addr)
+        .cert_path(args.certificate)
+        .key_path(args.private_key)
+        .client_ca_path(args.client_ca)
+        .min_version(min_version)
+        .cipher_suites(Vec::<CipherSuite>::new())
+        .require_client_auth(args.tls_require_client_auth)
+        .build()?;
+
     info!("Starting Wasmbed Gateway with custom TLS implementation");
     info!("Namespace: {}", args.namespace);
     info!("Pod: {}/{}", args.pod_namespace, args.pod_name);
-    
+
     // Start the TLS server
     tls_server.start().await?;
 
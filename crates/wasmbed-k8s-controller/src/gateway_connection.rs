@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Long-lived WebSocket connection to the Gateway, so the controller can
+//! react to gateway-pushed device/application events instead of relying
+//! solely on HTTP polling. Each connection attempt runs a write half
+//! (shared via `Arc<Mutex<..>>` between the heartbeat task and the
+//! identify handshake) and a read loop that dispatches inbound messages as
+//! `GatewayEvent`s; both stop together via a `tokio::sync::broadcast` kill
+//! signal. A supervising task reconnects with exponential backoff and
+//! re-sends the identify/subscribe payload on every attempt.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, warn};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// Events pushed by the gateway over the WebSocket connection
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatewayEvent {
+    DeviceConnected { device_name: String },
+    DeviceDisconnected { device_name: String },
+    ApplicationPhaseChanged {
+        app_namespace: String,
+        app_name: String,
+        device_name: String,
+        phase: String,
+    },
+}
+
+/// Handle to a long-lived, auto-reconnecting WebSocket connection to a
+/// gateway. Dropping it leaks the background task; call `shutdown` to stop
+/// it and its per-connection tasks cleanly.
+pub struct GatewayConnection {
+    kill_tx: broadcast::Sender<()>,
+    supervisor: tokio::task::JoinHandle<()>,
+}
+
+impl GatewayConnection {
+    /// Connect to `ws_url`, re-sending `identify_payload` on every
+    /// (re)connect, and return the connection handle plus a channel of
+    /// events dispatched from the gateway.
+    pub fn spawn(ws_url: String, identify_payload: serde_json::Value) -> (Self, mpsc::UnboundedReceiver<GatewayEvent>) {
+        let (kill_tx, _) = broadcast::channel(1);
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+        let kill_rx = kill_tx.subscribe();
+        let supervisor = tokio::spawn(Self::supervise(ws_url, identify_payload, event_tx, kill_rx));
+
+        (Self { kill_tx, supervisor }, event_rx)
+    }
+
+    /// Stop the connection and all of its background tasks
+    pub async fn shutdown(self) {
+        let _ = self.kill_tx.send(());
+        let _ = self.supervisor.await;
+    }
+
+    /// Reconnect loop: keep a live connection open, backing off
+    /// exponentially between attempts, until told to stop.
+    async fn supervise(ws_url: String, identify_payload: serde_json::Value, event_tx: mpsc::UnboundedSender<GatewayEvent>, mut kill_rx: broadcast::Receiver<()>) {
+        let mut backoff = INITIAL_RECONNECT_DELAY;
+
+        loop {
+            tokio::select! {
+                _ = kill_rx.recv() => return,
+                result = Self::connect_and_serve(&ws_url, &identify_payload, &event_tx, kill_rx.resubscribe()) => {
+                    match result {
+                        Ok(()) => {
+                            debug!("Gateway connection closed cleanly, reconnecting");
+                            backoff = INITIAL_RECONNECT_DELAY;
+                        }
+                        Err(e) => warn!("Gateway connection failed: {}", e),
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = kill_rx.recv() => return,
+                _ = tokio::time::sleep(backoff) => {}
+            }
+            backoff = std::cmp::min(backoff * 2, MAX_RECONNECT_DELAY);
+        }
+    }
+
+    /// Establish one WebSocket connection and run its read loop and
+    /// heartbeat task until the socket dies or `kill_rx` fires.
+    async fn connect_and_serve(ws_url: &str, identify_payload: &serde_json::Value, event_tx: &mpsc::UnboundedSender<GatewayEvent>, mut kill_rx: broadcast::Receiver<()>) -> Result<()> {
+        let tls_connector = Connector::Rustls(Arc::new(Self::tls_client_config()?));
+
+        let (ws_stream, _) = tokio_tungstenite::connect_async_tls_with_config(ws_url, None, false, Some(tls_connector))
+            .await
+            .context("Failed to connect to gateway websocket")?;
+
+        let (write, mut read) = ws_stream.split();
+        let write = Arc::new(Mutex::new(write));
+
+        write.lock().await
+            .send(Message::Text(identify_payload.to_string()))
+            .await
+            .context("Failed to send identify payload")?;
+
+        let last_ack = Arc::new(Mutex::new(Instant::now()));
+        let mut heartbeat_handle = tokio::spawn(Self::heartbeat_task(write.clone(), last_ack.clone(), kill_rx.resubscribe()));
+
+        let result = loop {
+            tokio::select! {
+                _ = kill_rx.recv() => break Ok(()),
+                _ = &mut heartbeat_handle => break Err(anyhow::anyhow!("Gateway heartbeat stopped: no ack or send failure")),
+                message = read.next() => {
+                    match message {
+                        Some(Ok(Message::Text(text))) if text == "heartbeat_ack" => {
+                            *last_ack.lock().await = Instant::now();
+                        },
+                        Some(Ok(Message::Text(text))) => {
+                            match serde_json::from_str::<GatewayEvent>(&text) {
+                                Ok(event) => { let _ = event_tx.send(event); },
+                                Err(e) => warn!("Failed to parse gateway event: {}", e),
+                            }
+                        },
+                        Some(Ok(Message::Ping(_) | Message::Pong(_))) => {},
+                        Some(Ok(Message::Close(_))) | None => break Ok(()),
+                        Some(Ok(_)) => {},
+                        Some(Err(e)) => break Err(anyhow::anyhow!("Gateway websocket error: {}", e)),
+                    }
+                }
+            }
+        };
+
+        heartbeat_handle.abort();
+        result
+    }
+
+    /// Send a keepalive every `HEARTBEAT_INTERVAL`; if two consecutive
+    /// intervals pass without an ack, give up on this connection so the
+    /// supervisor reconnects.
+    async fn heartbeat_task(write: Arc<Mutex<WsSink>>, last_ack: Arc<Mutex<Instant>>, mut kill_rx: broadcast::Receiver<()>) {
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = kill_rx.recv() => return,
+                _ = interval.tick() => {
+                    if write.lock().await.send(Message::Text("heartbeat".to_string())).await.is_err() {
+                        return;
+                    }
+
+                    if last_ack.lock().await.elapsed() > HEARTBEAT_INTERVAL * 2 {
+                        warn!("No heartbeat ack for two consecutive intervals, dropping gateway connection");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn tls_client_config() -> Result<rustls::ClientConfig> {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_native_certs::load_native_certs().context("Failed to load native root certificates")? {
+            let _ = roots.add(cert);
+        }
+
+        Ok(rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth())
+    }
+}
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Routes deploy/control requests to one of several gateways fronting
+//! different device groups, instead of a single hard-coded gateway URL.
+//! Gateways are registered with a label selector (matched against a
+//! device's Kubernetes labels) and a credential with a validity window;
+//! `route` skips gateways whose credential is expired or revoked and
+//! load-balances round-robin across the remaining candidates. The table is
+//! refreshed at runtime from the `wasmbed-gateways` ConfigMap so gateways
+//! can be added or drained without a controller restart.
+//!
+//! This is also where zone/region-aware placement lives: label selectors are
+//! the mechanism, since labels already express zone membership (e.g. a
+//! `topology.wasmbed.io/zone=eu-west` selector on a gateway entry). An
+//! earlier attempt at a separate scoring-based placement module
+//! (`assign_replicas`/`DeviceCandidate`) was built in the disconnected
+//! `retrospect/crates/wasmbed-gateway` tree (no caller anywhere in
+//! `crates/`) and was removed rather than wired in: it would have been a
+//! second, competing placement authority alongside this registry rather
+//! than an extension of it.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use kube::{api::Api, client::Client};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::GatewayClient;
+
+/// A gateway's access credential, checked before routing any request to it
+#[derive(Clone, Debug)]
+pub struct GatewayCredential {
+    pub key: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub revoked: bool,
+}
+
+impl GatewayCredential {
+    /// Whether the credential is usable right now: not revoked and within
+    /// its not-before/not-after window
+    pub fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && now >= self.not_before && now <= self.not_after
+    }
+}
+
+/// One registered gateway: its endpoint, the device labels it's
+/// responsible for, and its access credential
+pub struct GatewayEntry {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub credential: GatewayCredential,
+    pub client: std::sync::Arc<GatewayClient>,
+}
+
+/// Gateway entry as read from the `wasmbed-gateways` ConfigMap: one JSON
+/// value per gateway, keyed by gateway name
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GatewayConfigEntry {
+    url: String,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    key: String,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+    #[serde(default)]
+    revoked: bool,
+}
+
+/// Multi-gateway routing table, refreshable at runtime from the
+/// `wasmbed-gateways` ConfigMap
+pub struct GatewayRegistry {
+    entries: RwLock<Vec<GatewayEntry>>,
+    next: AtomicUsize,
+}
+
+impl GatewayRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Replace the registry contents wholesale, e.g. after reading the
+    /// `wasmbed-gateways` ConfigMap
+    pub async fn replace(&self, entries: Vec<GatewayEntry>) {
+        *self.entries.write().await = entries;
+    }
+
+    /// Re-read the `wasmbed-gateways` ConfigMap in the `wasmbed` namespace
+    /// and replace the registry with its contents. A missing ConfigMap
+    /// clears the registry rather than erroring, since a fleet with no
+    /// gateways configured yet is a valid (if inert) state.
+    pub async fn refresh_from_cluster(&self, client: &Client) -> Result<()> {
+        let config_api: Api<k8s_openapi::api::core::v1::ConfigMap> = Api::namespaced(client.clone(), "wasmbed");
+
+        let data = match config_api.get("wasmbed-gateways").await {
+            Ok(config_map) => config_map.data.unwrap_or_default(),
+            Err(kube::Error::Api(kube::core::ErrorResponse { code: 404, .. })) => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let entries = data.into_iter().filter_map(|(name, raw)| {
+            match serde_json::from_str::<GatewayConfigEntry>(&raw) {
+                Ok(parsed) => Some(GatewayEntry {
+                    client: std::sync::Arc::new(GatewayClient::new(parsed.url, parsed.key.clone())),
+                    name,
+                    labels: parsed.labels,
+                    credential: GatewayCredential {
+                        key: parsed.key,
+                        not_before: parsed.not_before,
+                        not_after: parsed.not_after,
+                        revoked: parsed.revoked,
+                    },
+                }),
+                Err(e) => {
+                    warn!("Skipping malformed entry for gateway {} in wasmbed-gateways ConfigMap: {}", name, e);
+                    None
+                },
+            }
+        }).collect();
+
+        self.replace(entries).await;
+        Ok(())
+    }
+
+    /// Pick a credential-valid gateway whose labels are all present and
+    /// matching in `device_labels`, load-balancing round-robin across ties.
+    /// A gateway with no labels of its own matches every device, serving as
+    /// a default/catch-all. Gateways with an invalid credential are skipped
+    /// and the rejection is logged; gateway health is not checked.
+    pub async fn route(&self, device_labels: &BTreeMap<String, String>) -> Result<std::sync::Arc<GatewayClient>> {
+        let entries = self.entries.read().await;
+        if entries.is_empty() {
+            return Err(anyhow!("no gateways registered"));
+        }
+
+        let now = Utc::now();
+        let candidates: Vec<&GatewayEntry> = entries.iter()
+            .filter(|e| e.labels.iter().all(|(k, v)| device_labels.get(k) == Some(v)))
+            .filter(|e| {
+                let valid = e.credential.is_valid(now);
+                if !valid {
+                    warn!("Skipping gateway {} for routing: credential is {} (window {} .. {})",
+                        e.name,
+                        if e.credential.revoked { "revoked" } else { "outside its validity window" },
+                        e.credential.not_before, e.credential.not_after);
+                }
+                valid
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(anyhow!("no gateway with a valid credential matches the target device's labels"));
+        }
+
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % candidates.len();
+        Ok(candidates[index].client.clone())
+    }
+}
+
+impl Default for GatewayRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Prometheus metrics registry shared between the reconcile loop and the
+//! `/metrics` HTTP endpoint, so operators get real counters/gauges instead
+//! of a static status blob.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, GaugeVec, Opts, Registry, TextEncoder,
+};
+
+use wasmbed_k8s_resource::{ApplicationPhase, ApplicationStatistics};
+
+pub struct Metrics {
+    registry: Registry,
+    reconciliations_total: IntCounter,
+    reconciliation_errors_total: IntCounter,
+    reconciliation_duration_seconds: Histogram,
+    applications_by_phase: GaugeVec,
+    devices_by_status: GaugeVec,
+    rejected_transitions_total: IntCounterVec,
+    /// Last-known phase label per "namespace/name" key, so `applications_by_phase`
+    /// can be kept accurate by decrementing the previous phase's gauge.
+    phase_cache: Mutex<BTreeMap<String, String>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let reconciliations_total = IntCounter::new(
+            "wasmbed_reconciliations_total",
+            "Total number of Application reconcile invocations",
+        ).expect("valid metric");
+
+        let reconciliation_errors_total = IntCounter::new(
+            "wasmbed_reconciliation_errors_total",
+            "Total number of Application reconcile invocations that returned an error",
+        ).expect("valid metric");
+
+        let reconciliation_duration_seconds = Histogram::with_opts(HistogramOpts::new(
+            "wasmbed_reconciliation_duration_seconds",
+            "Time taken to reconcile a single Application",
+        )).expect("valid metric");
+
+        let applications_by_phase = GaugeVec::new(
+            Opts::new("wasmbed_applications_by_phase", "Number of Applications currently in each phase"),
+            &["phase"],
+        ).expect("valid metric");
+
+        let devices_by_status = GaugeVec::new(
+            Opts::new("wasmbed_application_devices", "Number of devices in each deployment status, per application"),
+            &["application", "status"],
+        ).expect("valid metric");
+
+        let rejected_transitions_total = IntCounterVec::new(
+            Opts::new("wasmbed_rejected_transitions_total", "Number of invalid Application phase transitions rejected"),
+            &["application"],
+        ).expect("valid metric");
+
+        registry.register(Box::new(reconciliations_total.clone())).expect("register metric");
+        registry.register(Box::new(reconciliation_errors_total.clone())).expect("register metric");
+        registry.register(Box::new(reconciliation_duration_seconds.clone())).expect("register metric");
+        registry.register(Box::new(applications_by_phase.clone())).expect("register metric");
+        registry.register(Box::new(devices_by_status.clone())).expect("register metric");
+        registry.register(Box::new(rejected_transitions_total.clone())).expect("register metric");
+
+        Self {
+            registry,
+            reconciliations_total,
+            reconciliation_errors_total,
+            reconciliation_duration_seconds,
+            applications_by_phase,
+            devices_by_status,
+            rejected_transitions_total,
+            phase_cache: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record one reconcile invocation's outcome and latency
+    pub fn record_reconciliation(&self, duration: Duration, succeeded: bool) {
+        self.reconciliations_total.inc();
+        self.reconciliation_duration_seconds.observe(duration.as_secs_f64());
+        if !succeeded {
+            self.reconciliation_errors_total.inc();
+        }
+    }
+
+    /// Update the per-phase Application gauge, moving `app_key`
+    /// (`namespace/name`) from whatever phase it was last recorded in
+    pub fn record_phase(&self, app_key: &str, phase: ApplicationPhase) {
+        let phase_label = format!("{:?}", phase);
+        let mut cache = self.phase_cache.lock().unwrap();
+
+        if let Some(previous) = cache.insert(app_key.to_string(), phase_label.clone()) {
+            if previous != phase_label {
+                self.applications_by_phase.with_label_values(&[&previous]).dec();
+                self.applications_by_phase.with_label_values(&[&phase_label]).inc();
+            }
+        } else {
+            self.applications_by_phase.with_label_values(&[&phase_label]).inc();
+        }
+    }
+
+    /// Record the device-count breakdown for an application, as already
+    /// computed by `update_application_status_with_devices`
+    pub fn record_device_statistics(&self, app_name: &str, stats: &ApplicationStatistics) {
+        self.devices_by_status.with_label_values(&[app_name, "total"]).set(stats.total_devices as f64);
+        self.devices_by_status.with_label_values(&[app_name, "running"]).set(stats.running_devices as f64);
+        self.devices_by_status.with_label_values(&[app_name, "failed"]).set(stats.failed_devices as f64);
+        self.devices_by_status.with_label_values(&[app_name, "stopped"]).set(stats.stopped_devices as f64);
+    }
+
+    /// Record a rejected (invalid) phase transition for `app_name`
+    pub fn record_rejected_transition(&self, app_name: &str) {
+        self.rejected_transitions_total.with_label_values(&[app_name]).inc();
+    }
+
+    /// Render all registered metrics in Prometheus text exposition format
+    pub fn encode(&self) -> String {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&metric_families, &mut buffer).expect("encode metrics");
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Pluggable outbound notification channels for application failures and
+//! unhealthy devices, configured via the `wasmbed-notifiers` ConfigMap so
+//! operators can wire up alerting without a controller rebuild.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+
+/// Payload delivered to every configured `Notifier` on a failure-worthy event
+#[derive(Clone, Debug, Serialize)]
+pub struct NotificationEvent {
+    pub app_name: String,
+    pub app_namespace: String,
+    pub device_names: Vec<String>,
+    pub reason: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// A channel capable of delivering a `NotificationEvent` to an operator
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, event: &'a NotificationEvent) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Posts the event as JSON to a generic HTTP webhook URL
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, event: &'a NotificationEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.client.post(&self.url).json(event).send().await?.error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// Posts the event to an APNs/FCM-style push gateway, bearer-authenticated
+pub struct PushNotifier {
+    client: reqwest::Client,
+    url: String,
+    api_key: String,
+}
+
+impl PushNotifier {
+    pub fn new(url: String, api_key: String) -> Self {
+        Self { client: reqwest::Client::new(), url, api_key }
+    }
+}
+
+impl Notifier for PushNotifier {
+    fn notify<'a>(&'a self, event: &'a NotificationEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.client
+                .post(&self.url)
+                .bearer_auth(&self.api_key)
+                .json(event)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// Parsed `wasmbed-notifiers` ConfigMap: the notifier channels to fire and
+/// the cooldown between repeated notifications for the same app+reason+device set
+pub struct NotifierConfig {
+    pub notifiers: Vec<Arc<dyn Notifier>>,
+    pub cooldown: Duration,
+}
+
+const DEFAULT_COOLDOWN_SECS: u64 = 300;
+
+impl NotifierConfig {
+    pub fn from_configmap_data(data: &BTreeMap<String, String>) -> Self {
+        let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+
+        if let Some(url) = data.get("webhookUrl") {
+            notifiers.push(Arc::new(WebhookNotifier::new(url.clone())));
+        }
+
+        if let (Some(url), Some(api_key)) = (data.get("pushUrl"), data.get("pushApiKey")) {
+            notifiers.push(Arc::new(PushNotifier::new(url.clone(), api_key.clone())));
+        }
+
+        let cooldown = data.get("cooldownSeconds")
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_COOLDOWN_SECS));
+
+        Self { notifiers, cooldown }
+    }
+}
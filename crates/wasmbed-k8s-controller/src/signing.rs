@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Verifies ECDSA P-256 signatures over deployed WASM bytecode against a
+//! configurable trust store, so a tampered `ApplicationSpec.wasm_bytes`
+//! never reaches `deploy_to_device`.
+
+use std::collections::BTreeMap;
+
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::DecodePublicKey;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SigningError {
+    #[error("application spec has no signature, but signing is required")]
+    MissingSignature,
+    #[error("key id '{0}' is not in the trust store")]
+    UnknownKeyId(String),
+    #[error("signature is not valid base64/DER: {0}")]
+    InvalidSignatureEncoding(String),
+    #[error("ECDSA signature verification failed")]
+    VerificationFailed,
+}
+
+/// Trusted ECDSA P-256 public keys, keyed by `key_id`, loaded from the
+/// `wasmbed-trusted-keys` ConfigMap so operators can rotate keys without a
+/// controller rebuild.
+#[derive(Default, Clone)]
+pub struct TrustStore {
+    keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl TrustStore {
+    /// Parse `data` (ConfigMap `.data`, `key_id` -> PEM-encoded SPKI public
+    /// key) into a `TrustStore`. Entries that fail to parse are skipped
+    /// with a warning rather than failing the whole load.
+    pub fn from_configmap_data(data: &BTreeMap<String, String>) -> Self {
+        let mut keys = BTreeMap::new();
+        for (key_id, pem) in data {
+            match VerifyingKey::from_public_key_pem(pem) {
+                Ok(key) => {
+                    keys.insert(key_id.clone(), key);
+                }
+                Err(e) => tracing::warn!("Skipping trusted key '{}': {}", key_id, e),
+            }
+        }
+        Self { keys }
+    }
+
+    /// True when no trusted keys are configured, meaning signature
+    /// verification is not required for this cluster.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Verify `wasm_bytes` against `signature_der` (DER-encoded ECDSA P-256
+    /// signature) using the key registered under `key_id`. `nonce`, if
+    /// present, is folded into the signed digest, so the signature only
+    /// verifies together with that nonce; revoked nonces are not tracked.
+    pub fn verify(
+        &self,
+        key_id: &str,
+        wasm_bytes: &[u8],
+        signature_der: &[u8],
+        nonce: Option<&str>,
+    ) -> Result<(), SigningError> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| SigningError::UnknownKeyId(key_id.to_string()))?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(wasm_bytes);
+        if let Some(nonce) = nonce {
+            hasher.update(nonce.as_bytes());
+        }
+        let digest = hasher.finalize();
+
+        let signature = Signature::from_der(signature_der)
+            .map_err(|e| SigningError::InvalidSignatureEncoding(e.to_string()))?;
+
+        key.verify_prehash(&digest, &signature)
+            .map_err(|_| SigningError::VerificationFailed)
+    }
+}
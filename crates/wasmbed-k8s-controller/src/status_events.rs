@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Broadcast channel of Application status changes, so HTTP subscribers can
+//! watch a deployment progress over Server-Sent Events instead of polling
+//! the Kubernetes API. Publishers never block on subscribers: a slow
+//! subscriber falls behind and misses events (`tokio::sync::broadcast`'s
+//! lagged-receiver semantics) rather than slowing down the reconciler.
+
+use tokio::sync::broadcast;
+
+/// Channel capacity; a subscriber that falls this far behind the most
+/// recent publish gets a `RecvError::Lagged` and skips ahead.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A status change pushed to subscribers of an Application's event stream
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusEvent {
+    PhaseChanged {
+        app_namespace: String,
+        app_name: String,
+        phase: String,
+        message: String,
+    },
+    DeviceAdded {
+        app_namespace: String,
+        app_name: String,
+        device_name: String,
+    },
+    DeviceFailed {
+        app_namespace: String,
+        app_name: String,
+        device_name: String,
+    },
+    StatisticsUpdated {
+        app_namespace: String,
+        app_name: String,
+        total_devices: u32,
+        running_devices: u32,
+        failed_devices: u32,
+    },
+}
+
+impl StatusEvent {
+    /// Name of the Application this event is about, used by subscribers to
+    /// filter the shared broadcast stream down to one Application.
+    pub fn app_name(&self) -> &str {
+        match self {
+            StatusEvent::PhaseChanged { app_name, .. }
+            | StatusEvent::DeviceAdded { app_name, .. }
+            | StatusEvent::DeviceFailed { app_name, .. }
+            | StatusEvent::StatisticsUpdated { app_name, .. } => app_name,
+        }
+    }
+}
+
+/// Shared publish/subscribe handle for `StatusEvent`s
+#[derive(Clone)]
+pub struct StatusEventBus {
+    sender: broadcast::Sender<StatusEvent>,
+}
+
+impl StatusEventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish an event to all current subscribers; a no-op if nobody is listening
+    pub fn publish(&self, event: StatusEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to the full, unfiltered stream of events
+    pub fn subscribe(&self) -> broadcast::Receiver<StatusEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for StatusEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -36,6 +36,66 @@ pub struct ApplicationSpec {
     /// Application metadata
     #[serde(default)]
     pub metadata: Option<ApplicationMetadata>,
+
+    /// DER-encoded ECDSA P-256 signature over `wasm_bytes` (base64 encoded),
+    /// checked against the trusted key named by `key_id` before deployment
+    #[serde(default)]
+    pub signature: Option<String>,
+
+    /// Id of the trusted public key `signature` was produced with
+    #[serde(default, rename = "keyId")]
+    pub key_id: Option<String>,
+
+    /// Caller-chosen nonce folded into the signed digest, so the signature
+    /// only verifies together with this nonce; no list of revoked nonces is
+    /// checked
+    #[serde(default)]
+    pub nonce: Option<String>,
+
+    /// Staged/canary rollout policy. When set, `Deploying` fans the
+    /// application out in ordered waves instead of to every target device
+    /// at once; when absent, deployment behaves as before (single wave).
+    #[serde(default, rename = "rolloutPolicy")]
+    pub rollout_policy: Option<RolloutPolicy>,
+
+    /// When `true`, `Deploying` uses a two-phase-commit handshake: every
+    /// target device must acknowledge a "prepare" before any device is
+    /// told to start, so a failed prepare leaves no device started. If a
+    /// commit fails after every prepare succeeded, the devices already
+    /// committed keep running and the application goes to `PartiallyRunning`.
+    #[serde(default, rename = "atomicDeployment")]
+    pub atomic_deployment: Option<bool>,
+}
+
+/// Staged/canary rollout policy for `ApplicationSpec`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RolloutPolicy {
+    /// Cumulative fraction of target devices deployed by the end of each
+    /// wave, e.g. `[0.1, 0.5, 1.0]` deploys to 10%, then 50%, then 100%
+    #[serde(default = "default_wave_fractions", rename = "waveFractions")]
+    pub wave_fractions: Vec<f32>,
+
+    /// Number of reconcile cycles to observe a wave's health before
+    /// advancing to the next one
+    #[serde(default = "default_soak_cycles", rename = "soakCycles")]
+    pub soak_cycles: u32,
+
+    /// Minimum ratio of Running to (Running + Failed) devices in the
+    /// active wave required to advance to the next wave
+    #[serde(default = "default_health_threshold", rename = "healthThreshold")]
+    pub health_threshold: f32,
+}
+
+fn default_wave_fractions() -> Vec<f32> {
+    vec![0.1, 0.5, 1.0]
+}
+
+fn default_soak_cycles() -> u32 {
+    3
+}
+
+fn default_health_threshold() -> f32 {
+    0.9
 }
 
 /// Target devices specification
@@ -145,10 +205,70 @@ pub struct ApplicationStatus {
     /// Last update timestamp
     #[serde(default)]
     pub last_updated: Option<String>,
-    
+
     /// Error message if any
     #[serde(default)]
     pub error: Option<String>,
+
+    /// Staged rollout progress, present while a `RolloutPolicy` is active
+    #[serde(default)]
+    pub rollout: Option<RolloutStatus>,
+
+    /// Audit trail of accepted phase transitions, oldest first, bounded to
+    /// the most recent `MAX_TRANSITION_HISTORY` entries
+    #[serde(default)]
+    pub transition_history: Vec<TransitionRecord>,
+}
+
+/// Maximum number of entries kept in `ApplicationStatus::transition_history`
+pub const MAX_TRANSITION_HISTORY: usize = 20;
+
+/// One recorded phase transition, forming an operator-visible audit trail
+/// of how an Application arrived at its current phase
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TransitionRecord {
+    /// Phase transitioned from
+    pub from_phase: ApplicationPhase,
+
+    /// Phase transitioned to
+    pub to_phase: ApplicationPhase,
+
+    /// When the transition was recorded
+    pub timestamp: String,
+
+    /// Human-readable context for the transition
+    pub message: String,
+}
+
+/// Progress of a staged/canary rollout, persisted so wave advancement is
+/// stateless across reconciles
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RolloutStatus {
+    /// Index into `RolloutPolicy::wave_fractions` of the currently active wave
+    pub current_wave: u32,
+
+    /// Reconcile cycles the current wave has been observed for so far
+    pub soak_cycles_elapsed: u32,
+
+    /// Outcome recorded for each wave that has been deployed
+    #[serde(default)]
+    pub wave_outcomes: Vec<WaveOutcome>,
+}
+
+/// Health snapshot for one rollout wave
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct WaveOutcome {
+    /// Index into `RolloutPolicy::wave_fractions` this outcome covers
+    pub wave_index: u32,
+
+    /// Devices included in this wave
+    pub devices: Vec<String>,
+
+    /// Devices in this wave currently `Running`
+    pub healthy: u32,
+
+    /// Devices in this wave currently `Failed`
+    pub failed: u32,
 }
 
 /// Application phase
@@ -158,6 +278,8 @@ pub enum ApplicationPhase {
     Creating,
     /// Application is being deployed to devices
     Deploying,
+    /// Application is being deployed in ordered waves under a `RolloutPolicy`
+    RollingOut,
     /// Application is running on all target devices
     Running,
     /// Application is partially running
@@ -178,9 +300,17 @@ impl ApplicationPhase {
         match (current_phase, new_phase) {
             // Valid transitions
             (ApplicationPhase::Creating, ApplicationPhase::Deploying) => true,
+            (ApplicationPhase::Creating, ApplicationPhase::PartiallyRunning) => true,
+            (ApplicationPhase::Creating, ApplicationPhase::Failed) => true,
             (ApplicationPhase::Deploying, ApplicationPhase::Running) => true,
+            (ApplicationPhase::Deploying, ApplicationPhase::RollingOut) => true,
             (ApplicationPhase::Deploying, ApplicationPhase::PartiallyRunning) => true,
             (ApplicationPhase::Deploying, ApplicationPhase::Failed) => true,
+            (ApplicationPhase::RollingOut, ApplicationPhase::RollingOut) => true,
+            (ApplicationPhase::RollingOut, ApplicationPhase::Running) => true,
+            (ApplicationPhase::RollingOut, ApplicationPhase::PartiallyRunning) => true,
+            (ApplicationPhase::RollingOut, ApplicationPhase::Failed) => true,
+            (ApplicationPhase::RollingOut, ApplicationPhase::Deleting) => true,
             (ApplicationPhase::PartiallyRunning, ApplicationPhase::Running) => true,
             (ApplicationPhase::PartiallyRunning, ApplicationPhase::Failed) => true,
             (ApplicationPhase::Running, ApplicationPhase::Stopping) => true,
@@ -237,12 +367,17 @@ pub struct DeviceApplicationStatus {
 pub enum DeviceApplicationPhase {
     /// Application is being deployed
     Deploying,
+    /// WASM module staged on the device via a two-phase-commit "prepare",
+    /// but not yet told to start
+    Prepared,
     /// Application is running
     Running,
     /// Application has failed
     Failed,
     /// Application is stopped
     Stopped,
+    /// A prepared-but-uncommitted module was rolled back via "abort"
+    Aborted,
 }
 
 impl DeviceApplicationPhase {
@@ -252,6 +387,12 @@ impl DeviceApplicationPhase {
             // Valid transitions
             (DeviceApplicationPhase::Deploying, DeviceApplicationPhase::Running) => true,
             (DeviceApplicationPhase::Deploying, DeviceApplicationPhase::Failed) => true,
+            (DeviceApplicationPhase::Deploying, DeviceApplicationPhase::Prepared) => true,
+            (DeviceApplicationPhase::Prepared, DeviceApplicationPhase::Running) => true,
+            (DeviceApplicationPhase::Prepared, DeviceApplicationPhase::Failed) => true,
+            (DeviceApplicationPhase::Prepared, DeviceApplicationPhase::Aborted) => true,
+            (DeviceApplicationPhase::Aborted, DeviceApplicationPhase::Deploying) => true,
+            (DeviceApplicationPhase::Aborted, DeviceApplicationPhase::Prepared) => true,
             (DeviceApplicationPhase::Running, DeviceApplicationPhase::Stopped) => true,
             (DeviceApplicationPhase::Running, DeviceApplicationPhase::Failed) => true,
             (DeviceApplicationPhase::Stopped, DeviceApplicationPhase::Deploying) => true,
@@ -269,6 +410,23 @@ impl DeviceApplicationPhase {
     pub fn default() -> Self {
         DeviceApplicationPhase::Deploying
     }
+
+    /// Lowercase, stable wire-format name for this phase, used by the
+    /// gateway's `controller/events` websocket push
+    /// (`GatewayEvent::ApplicationPhaseChanged`). Deliberately not `Debug`
+    /// (which renders the PascalCase variant name): the receiving end
+    /// matches against these exact strings, and `Debug`'s output isn't a
+    /// stable contract.
+    pub fn as_event_str(&self) -> &'static str {
+        match self {
+            DeviceApplicationPhase::Deploying => "deploying",
+            DeviceApplicationPhase::Prepared => "prepared",
+            DeviceApplicationPhase::Running => "running",
+            DeviceApplicationPhase::Failed => "failed",
+            DeviceApplicationPhase::Stopped => "stopped",
+            DeviceApplicationPhase::Aborted => "aborted",
+        }
+    }
 }
 
 /// Application metrics
@@ -346,3 +504,22 @@ impl Application {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_application_phase_event_str_is_lowercase() {
+        // Guards against re-introducing the `{:?}` (PascalCase) bug:
+        // `wasmbed-k8s-controller::apply_pushed_device_phase` matches these
+        // exact lowercase strings against the gateway's pushed
+        // `application_phase_changed` events.
+        assert_eq!(DeviceApplicationPhase::Deploying.as_event_str(), "deploying");
+        assert_eq!(DeviceApplicationPhase::Prepared.as_event_str(), "prepared");
+        assert_eq!(DeviceApplicationPhase::Running.as_event_str(), "running");
+        assert_eq!(DeviceApplicationPhase::Failed.as_event_str(), "failed");
+        assert_eq!(DeviceApplicationPhase::Stopped.as_event_str(), "stopped");
+        assert_eq!(DeviceApplicationPhase::Aborted.as_event_str(), "aborted");
+    }
+}
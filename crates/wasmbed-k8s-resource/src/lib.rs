@@ -11,7 +11,7 @@ mod device_client;
 mod application_client;
 
 pub use device::{Device, DeviceSpec, DevicePhase, DeviceStatus};
-pub use application::{Application, ApplicationSpec, ApplicationStatus, ApplicationPhase, DeviceApplicationStatus, DeviceApplicationPhase, ApplicationConfig, ApplicationMetadata, ApplicationMetrics, ApplicationStatistics, TargetDevices, DeviceSelectors, DeviceSelectorRequirement};
+pub use application::{Application, ApplicationSpec, ApplicationStatus, ApplicationPhase, DeviceApplicationStatus, DeviceApplicationPhase, ApplicationConfig, ApplicationMetadata, ApplicationMetrics, ApplicationStatistics, TargetDevices, DeviceSelectors, DeviceSelectorRequirement, RolloutPolicy, RolloutStatus, WaveOutcome, TransitionRecord, MAX_TRANSITION_HISTORY};
 
 #[cfg(feature = "client")]
 pub use device_client::DeviceStatusUpdate;
@@ -31,6 +31,9 @@ const SERVER_DEPLOY_APPLICATION: u32 = 5;
 const SERVER_STOP_APPLICATION: u32 = 6;
 const SERVER_REQUEST_DEVICE_INFO: u32 = 7;
 const SERVER_REQUEST_APPLICATION_STATUS: u32 = 8;
+const SERVER_PREPARE_APPLICATION: u32 = 9;
+const SERVER_COMMIT_APPLICATION: u32 = 10;
+const SERVER_ABORT_APPLICATION: u32 = 11;
 
 #[derive(Debug, Display, Error)]
 enum MessageDecodeError {
@@ -295,6 +298,15 @@ impl Encode<()> for ServerMessage {
                     e.null()?;
                 }
             },
+            ServerMessage::PrepareApplication { app_id, name, wasm_bytes } => {
+                e.array(4)?.u32(SERVER_PREPARE_APPLICATION)?.str(app_id)?.str(name)?.bytes(wasm_bytes)?;
+            },
+            ServerMessage::CommitApplication { app_id } => {
+                e.array(2)?.u32(SERVER_COMMIT_APPLICATION)?.str(app_id)?;
+            },
+            ServerMessage::AbortApplication { app_id } => {
+                e.array(2)?.u32(SERVER_ABORT_APPLICATION)?.str(app_id)?;
+            },
         }
         Ok(())
     }
@@ -375,6 +387,20 @@ impl<'b> Decode<'b, ()> for ServerMessage {
                 };
                 Ok(ServerMessage::RequestApplicationStatus { app_id })
             },
+            (SERVER_PREPARE_APPLICATION, 4) => {
+                let app_id = d.str()?.to_string();
+                let name = d.str()?.to_string();
+                let wasm_bytes = d.bytes()?.to_vec();
+                Ok(ServerMessage::PrepareApplication { app_id, name, wasm_bytes })
+            },
+            (SERVER_COMMIT_APPLICATION, 2) => {
+                let app_id = d.str()?.to_string();
+                Ok(ServerMessage::CommitApplication { app_id })
+            },
+            (SERVER_ABORT_APPLICATION, 2) => {
+                let app_id = d.str()?.to_string();
+                Ok(ServerMessage::AbortApplication { app_id })
+            },
             (SERVER_HEARTBEAT_ACK, _) | (SERVER_ENROLLMENT_ACCEPTED, _) | (SERVER_ENROLLMENT_COMPLETED, _) => {
                 Err(DecodeError::custom(
                     MessageDecodeError::UnexpectedArrayLength {
@@ -423,6 +449,22 @@ impl<'b> Decode<'b, ()> for ServerMessage {
                     },
                 ))
             },
+            (SERVER_PREPARE_APPLICATION, _) => {
+                Err(DecodeError::custom(
+                    MessageDecodeError::UnexpectedArrayLength {
+                        expected: 4,
+                        actual: array_len,
+                    },
+                ))
+            },
+            (SERVER_COMMIT_APPLICATION, _) | (SERVER_ABORT_APPLICATION, _) => {
+                Err(DecodeError::custom(
+                    MessageDecodeError::UnexpectedArrayLength {
+                        expected: 2,
+                        actual: array_len,
+                    },
+                ))
+            },
             _ => {
                 Err(DecodeError::custom(MessageDecodeError::UnknownTag { tag }))
             },
@@ -188,6 +188,31 @@ pub enum ServerMessage {
         /// Application ID (None for all applications)
         app_id: Option<alloc::string::String>,
     },
+
+    /// Stage a WASM module on the device without starting it, as the
+    /// "prepare" phase of a two-phase-commit deployment
+    PrepareApplication {
+        /// Application ID
+        app_id: alloc::string::String,
+        /// Application name
+        name: alloc::string::String,
+        /// WASM bytecode
+        wasm_bytes: alloc::vec::Vec<u8>,
+    },
+
+    /// Start a previously prepared WASM module, as the "commit" phase of a
+    /// two-phase-commit deployment
+    CommitApplication {
+        /// Application ID
+        app_id: alloc::string::String,
+    },
+
+    /// Discard a previously prepared WASM module, as the "abort" phase of a
+    /// two-phase-commit deployment
+    AbortApplication {
+        /// Application ID
+        app_id: alloc::string::String,
+    },
 }
 
 /// Application status enumeration
@@ -1,16 +1,26 @@
 // SPDX-License-Identifier: AGPL-3.0
 // Copyright © 2025 Wasmbed contributors
 
-use std::os::unix::net::UnixStream;
-use std::io::{Read, Write};
 use std::time::Duration;
-use std::thread;
+
+use ed25519_dalek::{Signer, SigningKey};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::time::MissedTickBehavior;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
 
 pub struct QemuSerialBridge {
     device_id: String,
     serial_socket_path: String,
     gateway_host: String,
     gateway_port: u16,
+    client_cert: Vec<rustls::Certificate>,
+    client_key: rustls::PrivateKey,
+    gateway_ca: rustls::Certificate,
+    /// This device's Ed25519 identity, used to prove possession of the
+    /// claimed public key during the enrollment challenge/response.
+    signing_key: SigningKey,
     connected: bool,
     enrolled: bool,
     serial_connected: bool,
@@ -19,7 +29,8 @@ pub struct QemuSerialBridge {
     applications: std::collections::HashMap<String, ApplicationInfo>,
     microros_active: bool,
     serial_stream: Option<UnixStream>,
-    tls_stream: Option<rustls::StreamOwned<rustls::ClientConnection, std::net::TcpStream>>,
+    tls_read: Option<BufReader<tokio::io::ReadHalf<TlsStream<TcpStream>>>>,
+    tls_write: Option<tokio::io::WriteHalf<TlsStream<TcpStream>>>,
 }
 
 #[derive(Clone)]
@@ -31,12 +42,36 @@ struct ApplicationInfo {
 }
 
 impl QemuSerialBridge {
-    pub fn new(device_id: String, serial_socket_path: String, gateway_host: String, gateway_port: u16) -> Self {
-        Self {
+    /// Create a new bridge configured for mutual TLS: `client_cert_path` and
+    /// `client_key_path` are this device's PEM-encoded certificate chain and
+    /// PKCS8 private key, and `gateway_ca_path` is the PEM-encoded CA the
+    /// gateway's own certificate must chain to.
+    pub fn new(
+        device_id: String,
+        serial_socket_path: String,
+        gateway_host: String,
+        gateway_port: u16,
+        client_cert_path: &str,
+        client_key_path: &str,
+        gateway_ca_path: &str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let client_cert = load_certs(client_cert_path)?;
+        let client_key = load_private_key(client_key_path)?;
+        let gateway_ca = load_certs(gateway_ca_path)?
+            .into_iter()
+            .next()
+            .ok_or("Gateway CA file contains no certificates")?;
+        let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+
+        Ok(Self {
             device_id,
             serial_socket_path,
             gateway_host,
             gateway_port,
+            client_cert,
+            client_key,
+            gateway_ca,
+            signing_key,
             connected: false,
             enrolled: false,
             serial_connected: false,
@@ -45,31 +80,32 @@ impl QemuSerialBridge {
             applications: std::collections::HashMap::new(),
             microros_active: false,
             serial_stream: None,
-            tls_stream: None,
-        }
+            tls_read: None,
+            tls_write: None,
+        })
     }
-    
-    pub fn connect_serial(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let stream = UnixStream::connect(&self.serial_socket_path)?;
+
+    pub async fn connect_serial(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let stream = UnixStream::connect(&self.serial_socket_path).await?;
         self.serial_stream = Some(stream);
         self.serial_connected = true;
         println!("[{}] Connected to QEMU serial socket: {}", self.device_id, self.serial_socket_path);
         Ok(())
     }
-    
-    pub fn send_serial_command(&mut self, command: &str) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn send_serial_command(&mut self, command: &str) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(ref mut stream) = self.serial_stream {
             let message = format!("{}\n", command);
-            stream.write_all(message.as_bytes())?;
+            stream.write_all(message.as_bytes()).await?;
             println!("[{}] Sent serial command: {}", self.device_id, command);
         }
         Ok(())
     }
-    
-    pub fn read_serial_response(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+
+    pub async fn read_serial_response(&mut self) -> Result<String, Box<dyn std::error::Error>> {
         if let Some(ref mut stream) = self.serial_stream {
             let mut buffer = [0; 1024];
-            let n = stream.read(&mut buffer)?;
+            let n = stream.read(&mut buffer).await?;
             let response = String::from_utf8_lossy(&buffer[..n]).to_string();
             println!("[{}] Received serial response: {}", self.device_id, response.trim());
             Ok(response)
@@ -77,39 +113,91 @@ impl QemuSerialBridge {
             Err("Serial stream not connected".into())
         }
     }
-    
-    pub fn connect_to_gateway(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn connect_to_gateway(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.serial_connected {
             return Err("Serial not connected".into());
         }
-        
+
         // Create TLS connection to gateway
-        let tcp_stream = std::net::TcpStream::connect(format!("{}:{}", self.gateway_host, self.gateway_port))?;
-        
-        // Create TLS client configuration
-        let mut config = rustls::ClientConfig::builder()
+        let tcp_stream = TcpStream::connect(format!("{}:{}", self.gateway_host, self.gateway_port)).await?;
+
+        // Trust only certificates chaining to our pinned gateway CA, and
+        // authenticate ourselves to the gateway with our own client
+        // certificate so it can verify our identity before enrollment.
+        let mut gateway_roots = rustls::RootCertStore::empty();
+        gateway_roots.add(&self.gateway_ca)?;
+
+        let config = rustls::ClientConfig::builder()
             .with_safe_defaults()
-            .with_custom_certificate_verifier(std::sync::Arc::new(NoVerifier))
-            .with_no_client_auth();
-        
-        let connector = rustls::ClientConnection::new(std::sync::Arc::new(config), self.gateway_host.as_str().try_into()?)?;
-        let tls_stream = rustls::StreamOwned::new(connector, tcp_stream);
-        
-        self.tls_stream = Some(tls_stream);
+            .with_root_certificates(gateway_roots)
+            .with_client_auth_cert(self.client_cert.clone(), self.client_key.clone())?;
+
+        let connector = TlsConnector::from(std::sync::Arc::new(config));
+        let domain: rustls::ServerName = self.gateway_host.as_str().try_into()?;
+        let tls_stream = connector.connect(domain, tcp_stream).await?;
+
+        let (read_half, write_half) = tokio::io::split(tls_stream);
+        self.tls_read = Some(BufReader::new(read_half));
+        self.tls_write = Some(write_half);
+
         self.connected = true;
         println!("[{}] Connected to gateway at {}:{}", self.device_id, self.gateway_host, self.gateway_port);
         Ok(())
     }
-    
-    pub fn enroll_device(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn enroll_device(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.connected {
             return Err("Not connected to gateway".into());
         }
-        
-        let enrollment_msg = serde_json::json!({
-            "type": "enrollment",
+
+        // Wait for the gateway's enrollment challenge before presenting any
+        // identity, so we prove possession of our private key rather than
+        // asserting a public key the gateway has no reason to trust yet.
+        let mut challenge_line = String::new();
+        self.tls_read
+            .as_mut()
+            .ok_or("Not connected to gateway")?
+            .read_line(&mut challenge_line)
+            .await?;
+        let challenge: serde_json::Value = serde_json::from_str(&challenge_line)?;
+        if challenge.get("type").and_then(|t| t.as_str()) != Some("challenge") {
+            return Err(format!("Expected enrollment challenge, got: {}", challenge_line.trim()).into());
+        }
+        let nonce_b64 = challenge
+            .get("nonce")
+            .and_then(|n| n.as_str())
+            .ok_or("Challenge message missing nonce")?;
+        let nonce = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, nonce_b64)?;
+
+        let attestation = self.respond_to_challenge(&nonce);
+        let response = self.send_gateway_message(&attestation).await?;
+        let response_data: serde_json::Value = serde_json::from_str(&response)?;
+
+        if response_data.get("status").and_then(|s| s.as_str()) == Some("success") {
+            self.enrolled = true;
+            println!("[{}] Successfully enrolled with gateway", self.device_id);
+        } else {
+            return Err(format!("Enrollment failed: {}", response_data.get("error").unwrap_or(&serde_json::Value::String("Unknown error".to_string()))).into());
+        }
+
+        Ok(())
+    }
+
+    /// Sign `nonce || device_id` with this device's Ed25519 key and build
+    /// the attestation message that proves control of the claimed public
+    /// key in response to an enrollment challenge.
+    fn respond_to_challenge(&self, nonce: &[u8]) -> serde_json::Value {
+        let mut signed_message = nonce.to_vec();
+        signed_message.extend_from_slice(self.device_id.as_bytes());
+        let signature = self.signing_key.sign(&signed_message);
+
+        serde_json::json!({
+            "type": "attestation",
             "device_id": self.device_id,
-            "public_key": format!("qemu_public_key_{}", self.device_id),
+            "public_key": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, self.signing_key.verifying_key().to_bytes()),
+            "nonce": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, nonce),
+            "signature": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, signature.to_bytes()),
             "device_type": "qemu-riscv32",
             "capabilities": ["wasm-execution", "tls-client", "microROS", "serial-communication"],
             "hardware_info": {
@@ -119,33 +207,14 @@ impl QemuSerialBridge {
                 "serial_socket": self.serial_socket_path
             },
             "timestamp": chrono::Utc::now().to_rfc3339()
-        });
-        
-        if let Some(ref mut stream) = self.tls_stream {
-            let message = format!("{}\n", enrollment_msg);
-            stream.write_all(message.as_bytes())?;
-            
-            let mut buffer = [0; 1024];
-            let n = stream.read(&mut buffer)?;
-            let response = String::from_utf8_lossy(&buffer[..n]).to_string();
-            let response_data: serde_json::Value = serde_json::from_str(&response)?;
-            
-            if response_data.get("status").and_then(|s| s.as_str()) == Some("success") {
-                self.enrolled = true;
-                println!("[{}] Successfully enrolled with gateway", self.device_id);
-            } else {
-                return Err(format!("Enrollment failed: {}", response_data.get("error").unwrap_or(&serde_json::Value::String("Unknown error".to_string()))).into());
-            }
-        }
-        
-        Ok(())
+        })
     }
-    
-    pub fn send_heartbeat(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn send_heartbeat(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.connected || !self.enrolled {
             return Err("Not connected or enrolled".into());
         }
-        
+
         let heartbeat_msg = serde_json::json!({
             "type": "heartbeat",
             "device_id": self.device_id,
@@ -159,146 +228,217 @@ impl QemuSerialBridge {
             "cpu_freq": 100,
             "timestamp": chrono::Utc::now().to_rfc3339()
         });
-        
-        if let Some(ref mut stream) = self.tls_stream {
-            let message = format!("{}\n", heartbeat_msg);
-            stream.write_all(message.as_bytes())?;
-            self.last_heartbeat = std::time::Instant::now();
-            println!("[{}] Heartbeat sent (Architecture: riscv32imac-unknown-none-elf)", self.device_id);
-        }
-        
+
+        self.write_gateway_message(&heartbeat_msg).await?;
+        self.last_heartbeat = std::time::Instant::now();
+        println!("[{}] Heartbeat sent (Architecture: riscv32imac-unknown-none-elf)", self.device_id);
+
         Ok(())
     }
-    
-    pub fn load_wasm_application(&mut self, app_id: &str, wasm_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn load_wasm_application(&mut self, app_id: &str, _wasm_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
         let app_info = ApplicationInfo {
             id: app_id.to_string(),
             status: "loaded".to_string(),
             loaded_at: std::time::Instant::now(),
             platform: "qemu-riscv32".to_string(),
         };
-        
+
         self.applications.insert(app_id.to_string(), app_info);
         println!("[{}] WASM application {} loaded on QEMU", self.device_id, app_id);
         Ok(())
     }
-    
-    pub fn execute_wasm_function(&mut self, app_id: &str, function_name: &str, args: Option<serde_json::Value>) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn execute_wasm_function(&mut self, app_id: &str, function_name: &str, args: Option<serde_json::Value>) -> Result<(), Box<dyn std::error::Error>> {
         if !self.applications.contains_key(app_id) {
             return Err(format!("Application {} not found", app_id).into());
         }
-        
+
         let command = if let Some(args) = args {
             format!("wasm_execute {} {} {}", app_id, function_name, args)
         } else {
             format!("wasm_execute {} {}", app_id, function_name)
         };
-        
-        self.send_serial_command(&command)?;
-        let response = self.read_serial_response()?;
-        
-        println!("[{}] Executed WASM function {} in {} on QEMU: {}", 
+
+        self.send_serial_command(&command).await?;
+        let response = self.read_serial_response().await?;
+
+        println!("[{}] Executed WASM function {} in {} on QEMU: {}",
                  self.device_id, function_name, app_id, response.trim());
         Ok(())
     }
-    
-    pub fn start_microros(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.send_serial_command("microros_start")?;
-        let response = self.read_serial_response()?;
-        
+
+    pub async fn start_microros(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.send_serial_command("microros_start").await?;
+        let response = self.read_serial_response().await?;
+
         if response.to_lowercase().contains("started") {
             self.microros_active = true;
             println!("[{}] microROS started on QEMU", self.device_id);
         }
-        
+
         Ok(())
     }
-    
-    pub fn publish_microros_topic(&mut self, topic: &str, data: serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+
+    pub async fn publish_microros_topic(&mut self, topic: &str, data: serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
         if !self.microros_active {
             return Err("microROS not active".into());
         }
-        
+
         let command = format!("microros_publish {} {}", topic, data);
-        self.send_serial_command(&command)?;
-        let response = self.read_serial_response()?;
-        
+        self.send_serial_command(&command).await?;
+        let _response = self.read_serial_response().await?;
+
         println!("[{}] QEMU published to topic {}: {}", self.device_id, topic, data);
         Ok(())
     }
-    
-    pub fn run_device_simulation(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+
+    /// Write one newline-delimited JSON message to the gateway without
+    /// waiting for a reply, used for fire-and-forget messages like
+    /// heartbeats.
+    async fn write_gateway_message(&mut self, message: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+        let stream = self.tls_write.as_mut().ok_or("Not connected to gateway")?;
+        let line = format!("{}\n", message);
+        stream.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// Write one newline-delimited JSON message to the gateway and wait for
+    /// its newline-delimited JSON reply, used for request/response exchanges
+    /// like enrollment.
+    async fn send_gateway_message(&mut self, message: &serde_json::Value) -> Result<String, Box<dyn std::error::Error>> {
+        self.write_gateway_message(message).await?;
+        let mut line = String::new();
+        let reader = self.tls_read.as_mut().ok_or("Not connected to gateway")?;
+        reader.read_line(&mut line).await?;
+        Ok(line)
+    }
+
+    /// Read and dispatch a single newline-delimited JSON command received
+    /// from the gateway, such as a request to deploy an application or stop
+    /// microROS.
+    async fn handle_gateway_command(&mut self, line: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let command: serde_json::Value = serde_json::from_str(line)?;
+        match command.get("type").and_then(|t| t.as_str()) {
+            Some("deploy_application") => {
+                let app_id = command.get("app_id").and_then(|v| v.as_str()).unwrap_or_default();
+                let wasm_data = command
+                    .get("wasm_bytes")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.as_bytes().to_vec())
+                    .unwrap_or_default();
+                self.load_wasm_application(app_id, &wasm_data).await?;
+            },
+            Some("execute_function") => {
+                let app_id = command.get("app_id").and_then(|v| v.as_str()).unwrap_or_default();
+                let function_name = command.get("function").and_then(|v| v.as_str()).unwrap_or_default();
+                let args = command.get("args").cloned();
+                self.execute_wasm_function(app_id, function_name, args).await?;
+            },
+            Some("stop_microros") => {
+                self.microros_active = false;
+                println!("[{}] microROS stopped by gateway command", self.device_id);
+            },
+            Some(other) => {
+                println!("[{}] Ignoring unknown gateway command: {}", self.device_id, other);
+            },
+            None => {
+                println!("[{}] Ignoring malformed gateway command: {}", self.device_id, line.trim());
+            },
+        }
+        Ok(())
+    }
+
+    pub async fn run_device_simulation(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         println!("\n=== Starting QEMU Device Simulation: {} ===", self.device_id);
-        
+
         // Connect to QEMU serial
-        self.connect_serial()?;
-        
+        self.connect_serial().await?;
+
         // Connect to gateway
-        self.connect_to_gateway()?;
-        
+        self.connect_to_gateway().await?;
+
         // Enroll device
-        self.enroll_device()?;
-        
+        self.enroll_device().await?;
+
         // Load sample WASM application
         let sample_wasm = b"qemu_wasm_binary_data";
-        self.load_wasm_application("microros-px4-bridge", sample_wasm)?;
-        
+        self.load_wasm_application("microros-px4-bridge", sample_wasm).await?;
+
         // Start microROS
-        self.start_microros()?;
-        
-        // Main device loop
-        let mut loop_count = 0;
+        self.start_microros().await?;
+
+        let mut heartbeat_timer = tokio::time::interval(self.heartbeat_interval);
+        heartbeat_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut sensor_timer = tokio::time::interval(Duration::from_secs(10));
+        sensor_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut publish_timer = tokio::time::interval(Duration::from_secs(20));
+        publish_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut status_timer = tokio::time::interval(Duration::from_secs(100));
+        status_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         loop {
-            loop_count += 1;
-            
-            // Send heartbeat every 30 seconds
-            if self.last_heartbeat.elapsed() >= self.heartbeat_interval {
-                self.send_heartbeat()?;
-            }
-            
-            // Execute WASM functions periodically
-            if loop_count % 100 == 0 {
-                let args = serde_json::json!({
-                    "sensor": "accelerometer",
-                    "platform": "qemu"
-                });
-                self.execute_wasm_function("microros-px4-bridge", "process_sensor_data", Some(args))?;
-            }
-            
-            // Publish microROS data periodically
-            if loop_count % 200 == 0 {
-                let data = serde_json::json!({
-                    "value": 42.5,
-                    "unit": "m/s²",
-                    "platform": "qemu"
-                });
-                self.publish_microros_topic("/sensor_data", data)?;
-            }
-            
-            // Show status periodically
-            if loop_count % 1000 == 0 {
-                println!("[{}] QEMU Status: {} applications, microROS: {}, Serial: {}", 
-                         self.device_id, self.applications.len(), self.microros_active, self.serial_connected);
+            let mut gateway_line = String::new();
+            tokio::select! {
+                _ = heartbeat_timer.tick() => {
+                    self.send_heartbeat().await?;
+                }
+
+                result = self.tls_read.as_mut().expect("connected to gateway").read_line(&mut gateway_line) => {
+                    let n = result?;
+                    if n == 0 {
+                        println!("[{}] Gateway closed the connection", self.device_id);
+                        break;
+                    }
+                    if let Err(e) = self.handle_gateway_command(&gateway_line).await {
+                        println!("[{}] Failed to handle gateway command: {}", self.device_id, e);
+                    }
+                }
+
+                _ = sensor_timer.tick() => {
+                    let args = serde_json::json!({
+                        "sensor": "accelerometer",
+                        "platform": "qemu"
+                    });
+                    self.execute_wasm_function("microros-px4-bridge", "process_sensor_data", Some(args)).await?;
+                }
+
+                _ = publish_timer.tick() => {
+                    let data = serde_json::json!({
+                        "value": 42.5,
+                        "unit": "m/s²",
+                        "platform": "qemu"
+                    });
+                    self.publish_microros_topic("/sensor_data", data).await?;
+                }
+
+                _ = status_timer.tick() => {
+                    println!("[{}] QEMU Status: {} applications, microROS: {}, Serial: {}",
+                             self.device_id, self.applications.len(), self.microros_active, self.serial_connected);
+                }
             }
-            
-            thread::sleep(Duration::from_millis(100)); // 100ms loop
         }
+
+        Ok(())
     }
 }
 
-// Custom certificate verifier for development
-struct NoVerifier;
-
-impl rustls::client::ServerCertVerifier for NoVerifier {
-    fn verify_server_cert(
-        &self,
-        _end_entity: &rustls::Certificate,
-        _intermediates: &[rustls::Certificate],
-        _server_name: &rustls::ServerName,
-        _scts: &mut dyn Iterator<Item = &[u8]>,
-        _ocsp_response: &[u8],
-        _now: std::time::SystemTime,
-    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
-        Ok(rustls::client::ServerCertVerified::assertion())
-    }
+/// Load a PEM-encoded certificate chain from disk
+fn load_certs(path: &str) -> Result<Vec<rustls::Certificate>, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    Ok(certs.into_iter().map(rustls::Certificate).collect())
+}
+
+/// Load a PEM-encoded PKCS8 private key from disk
+fn load_private_key(path: &str) -> Result<rustls::PrivateKey, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    let keys = rustls_pemfile::pkcs8_private_keys(&mut reader)?;
+    let key = keys.into_iter().next().ok_or("No PKCS8 private key found")?;
+    Ok(rustls::PrivateKey(key))
 }
@@ -16,31 +16,43 @@ async fn main() {
     
     // Parse command line arguments
     let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
-        eprintln!("Usage: {} <device-id> <serial-socket-path> [gateway-host] [gateway-port]", args[0]);
-        eprintln!("Example: {} qemu-device-1 /tmp/wasmbed-qemu-qemu-device-1.sock 172.19.0.2 30423", args[0]);
+    if args.len() < 6 {
+        eprintln!("Usage: {} <device-id> <serial-socket-path> <client-cert> <client-key> <gateway-ca> [gateway-host] [gateway-port]", args[0]);
+        eprintln!("Example: {} qemu-device-1 /tmp/wasmbed-qemu-qemu-device-1.sock device.crt device.key.pem8 gateway-ca.crt 172.19.0.2 30423", args[0]);
         process::exit(1);
     }
-    
+
     let device_id = args[1].clone();
     let serial_socket_path = args[2].clone();
-    let gateway_host = args.get(3).unwrap_or(&"172.19.0.2".to_string()).clone();
-    let gateway_port = args.get(4).unwrap_or(&"30423".to_string()).parse::<u16>().unwrap_or(30423);
-    
+    let client_cert_path = args[3].clone();
+    let client_key_path = args[4].clone();
+    let gateway_ca_path = args[5].clone();
+    let gateway_host = args.get(6).unwrap_or(&"172.19.0.2".to_string()).clone();
+    let gateway_port = args.get(7).unwrap_or(&"30423".to_string()).parse::<u16>().unwrap_or(30423);
+
     info!("Device ID: {}", device_id);
     info!("Serial Socket: {}", serial_socket_path);
     info!("Gateway: {}:{}", gateway_host, gateway_port);
-    
+
     // Create and run QEMU serial bridge
-    let mut bridge = QemuSerialBridge::new(
+    let mut bridge = match QemuSerialBridge::new(
         device_id.clone(),
         serial_socket_path,
         gateway_host,
         gateway_port,
-    );
-    
+        &client_cert_path,
+        &client_key_path,
+        &gateway_ca_path,
+    ) {
+        Ok(bridge) => bridge,
+        Err(e) => {
+            error!("Failed to initialize QEMU serial bridge TLS identity: {}", e);
+            process::exit(1);
+        }
+    };
+
     // Run the device simulation
-    if let Err(e) = bridge.run_device_simulation() {
+    if let Err(e) = bridge.run_device_simulation().await {
         error!("QEMU serial bridge error: {}", e);
         process::exit(1);
     }
@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: AGPL-3.0
+// Copyright © 2025 Wasmbed contributors
+
+//! Per-tenant TLS identity resolution based on the SNI server name a
+//! connecting device presents, so one gateway process can front several
+//! device fleets, each with its own certificate chain and client CA,
+//! instead of a single static cert/key pair for the whole process.
+//!
+//! Tenants are loaded from a directory of `{servername}.pem` /
+//! `{servername}.key` / `{servername}.ca.pem` triples and kept fresh by a
+//! background task that re-scans the directory periodically.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls_pki_types::CertificateDer;
+
+use crate::TlsUtils;
+
+/// One tenant's TLS identity: the certificate chain/key rustls presents to
+/// the client, and the client CA devices for this tenant must be signed by.
+struct TenantEntry {
+    certified_key: Arc<CertifiedKey>,
+    client_ca: CertificateDer<'static>,
+}
+
+/// Resolves a gateway's TLS identity per-connection from the SNI server
+/// name, falling back to a single default identity when no SNI name is
+/// sent or it doesn't match a known tenant.
+pub struct CertResolver {
+    dir: PathBuf,
+    tenants: RwLock<HashMap<String, TenantEntry>>,
+    default_tenant: RwLock<Option<String>>,
+    last_scan: RwLock<Option<SystemTime>>,
+}
+
+impl CertResolver {
+    /// Load every `{servername}.pem`/`{servername}.key`/`{servername}.ca.pem`
+    /// triple found directly inside `dir`. The first tenant loaded (in
+    /// directory order) becomes the fallback used when a connection carries
+    /// no SNI name, matching this gateway's historical single-identity
+    /// behavior.
+    pub fn from_directory(dir: &Path) -> Result<Self> {
+        let resolver = Self {
+            dir: dir.to_path_buf(),
+            tenants: RwLock::new(HashMap::new()),
+            default_tenant: RwLock::new(None),
+            last_scan: RwLock::new(None),
+        };
+        resolver.reload()?;
+        Ok(resolver)
+    }
+
+    /// Re-scan `dir` and replace the tenant table. Tenants that fail to
+    /// parse are skipped with a warning rather than aborting the reload, so
+    /// one malformed pair doesn't take every tenant offline.
+    pub fn reload(&self) -> Result<()> {
+        let mut tenants = HashMap::new();
+        let mut default_tenant = None;
+
+        let entries = std::fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read TLS tenant directory {:?}", self.dir))?;
+
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("pem") {
+                continue;
+            }
+            let stem = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(s) if !s.ends_with(".ca") => s.to_string(),
+                _ => continue,
+            };
+
+            let cert_path = self.dir.join(format!("{stem}.pem"));
+            let key_path = self.dir.join(format!("{stem}.key"));
+            let ca_path = self.dir.join(format!("{stem}.ca.pem"));
+
+            match load_tenant(&cert_path, &key_path, &ca_path) {
+                Ok(entry) => {
+                    if default_tenant.is_none() {
+                        default_tenant = Some(stem.clone());
+                    }
+                    tenants.insert(stem, entry);
+                },
+                Err(e) => {
+                    log::warn!("Skipping TLS tenant {} in {:?}: {}", stem, self.dir, e);
+                },
+            }
+        }
+
+        if tenants.is_empty() {
+            return Err(anyhow::anyhow!("No usable TLS tenants found in {:?}", self.dir));
+        }
+
+        *self.tenants.write().unwrap() = tenants;
+        *self.default_tenant.write().unwrap() = default_tenant;
+        *self.last_scan.write().unwrap() = Some(SystemTime::now());
+        Ok(())
+    }
+
+    /// Spawn a background task that re-reads the tenant directory every
+    /// `interval`, picking up certificate rotations or newly added/removed
+    /// tenants without restarting the gateway.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let resolver = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = resolver.reload() {
+                    log::warn!("TLS tenant directory reload failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// The client CA a given SNI server name's devices must be signed by,
+    /// used for the post-handshake client certificate check since rustls's
+    /// `ResolvesServerCert` hook only selects the server's own identity.
+    pub fn client_ca_for(&self, server_name: Option<&str>) -> Option<CertificateDer<'static>> {
+        let tenants = self.tenants.read().unwrap();
+        let name = server_name
+            .filter(|n| tenants.contains_key(*n))
+            .map(str::to_string)
+            .or_else(|| self.default_tenant.read().unwrap().clone())?;
+        tenants.get(&name).map(|t| t.client_ca.clone())
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        let tenants = self.tenants.read().unwrap();
+        let name = client_hello
+            .server_name()
+            .filter(|n| tenants.contains_key(*n))
+            .map(str::to_string)
+            .or_else(|| self.default_tenant.read().unwrap().clone())?;
+        tenants.get(&name).map(|t| t.certified_key.clone())
+    }
+}
+
+fn load_tenant(cert_path: &Path, key_path: &Path, ca_path: &Path) -> Result<TenantEntry> {
+    let cert_pem = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read {:?}", cert_path))?;
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read {:?}", key_path))?;
+    let ca_pem = std::fs::read(ca_path)
+        .with_context(|| format!("Failed to read {:?}", ca_path))?;
+
+    let cert = TlsUtils::parse_certificate(&cert_pem)?;
+    let key = TlsUtils::parse_private_key(&key_pem)?;
+    let client_ca = TlsUtils::parse_certificate(&ca_pem)?;
+
+    let signing_key = rustls::crypto::ring::sign::any_supported_type(&key)
+        .map_err(|e| anyhow::anyhow!("Unsupported private key in {:?}: {}", key_path, e))?;
+    let certified_key = Arc::new(CertifiedKey::new(vec![cert], signing_key));
+
+    Ok(TenantEntry { certified_key, client_ca })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_resolver() -> CertResolver {
+        CertResolver {
+            dir: PathBuf::new(),
+            tenants: RwLock::new(HashMap::new()),
+            default_tenant: RwLock::new(None),
+            last_scan: RwLock::new(None),
+        }
+    }
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("wasmbed-cert-resolver-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn client_ca_for_with_no_tenants_loaded_returns_none() {
+        let resolver = empty_resolver();
+        assert!(resolver.client_ca_for(Some("anything")).is_none());
+        assert!(resolver.client_ca_for(None).is_none());
+    }
+
+    #[test]
+    fn from_directory_errors_when_no_pem_files_present() {
+        let dir = unique_temp_dir("no-pem-files");
+        let result = CertResolver::from_directory(&dir);
+        assert!(result.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn from_directory_skips_unparseable_tenant_and_errors_when_none_remain() {
+        // A tenant whose cert/key/CA content doesn't parse should be skipped
+        // by `reload`, not crash the whole scan; with no other tenant to
+        // fall back to, `from_directory` still reports failure rather than
+        // silently starting with an empty tenant table.
+        let dir = unique_temp_dir("unparseable-tenant");
+        std::fs::write(dir.join("device-fleet.pem"), b"not a certificate").unwrap();
+        std::fs::write(dir.join("device-fleet.key"), b"not a key").unwrap();
+        std::fs::write(dir.join("device-fleet.ca.pem"), b"not a ca certificate").unwrap();
+
+        let result = CertResolver::from_directory(&dir);
+        assert!(result.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
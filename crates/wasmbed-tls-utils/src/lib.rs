@@ -18,15 +18,202 @@ use minicbor;
 // Re-export protocol types for compatibility
 pub use wasmbed_protocol::{ClientMessage, ServerMessage};
 
+mod cert_resolver;
+pub use cert_resolver::CertResolver;
+
 /// Custom TLS certificate and key utilities for Wasmbed
 pub struct TlsUtils;
 
+/// Minimum TLS protocol version a [`TlsServer`] should negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsVersion {
+    Tls12,
+    Tls13,
+}
+
+/// A cipher suite an operator can opt into via [`TlsServerBuilder::cipher_suites`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherSuite {
+    Tls13Aes256GcmSha384,
+    Tls13Aes128GcmSha256,
+    Tls13Chacha20Poly1305Sha256,
+    Tls12EcdheRsaWithAes256GcmSha384,
+    Tls12EcdheRsaWithAes128GcmSha256,
+}
+
+impl TlsVersion {
+    /// The rustls protocol version set this minimum version allows. TLS 1.2
+    /// still admits TLS 1.3 (a floor, not an exact pin); TLS 1.3 admits only
+    /// itself.
+    fn rustls_protocol_versions(self) -> &'static [&'static rustls::SupportedProtocolVersion] {
+        match self {
+            TlsVersion::Tls13 => &[&rustls::version::TLS13],
+            TlsVersion::Tls12 => &[&rustls::version::TLS12, &rustls::version::TLS13],
+        }
+    }
+}
+
+impl CipherSuite {
+    fn to_rustls(self) -> rustls::SupportedCipherSuite {
+        use rustls::crypto::ring::cipher_suite::*;
+        match self {
+            CipherSuite::Tls13Aes256GcmSha384 => TLS13_AES_256_GCM_SHA384,
+            CipherSuite::Tls13Aes128GcmSha256 => TLS13_AES_128_GCM_SHA256,
+            CipherSuite::Tls13Chacha20Poly1305Sha256 => TLS13_CHACHA20_POLY1305_SHA256,
+            CipherSuite::Tls12EcdheRsaWithAes256GcmSha384 => TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+            CipherSuite::Tls12EcdheRsaWithAes128GcmSha256 => TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+        }
+    }
+}
+
 /// Custom TLS Server implementation
 pub struct TlsServer {
     bind_addr: std::net::SocketAddr,
     server_cert: CertificateDer<'static>,
-    server_key: PrivatePkcs8KeyDer<'static>,
+    server_key: PrivateKeyDer<'static>,
     client_ca: CertificateDer<'static>,
+    min_version: TlsVersion,
+    cipher_suites: Vec<CipherSuite>,
+    require_client_auth: bool,
+}
+
+/// Fluent builder for [`TlsServer`] that accepts PEM file paths directly
+/// instead of requiring callers to hand-read and parse them, and exposes the
+/// protocol knobs operators need without recompiling: restricting the
+/// minimum TLS version, picking cipher suites, or making client
+/// certificate auth optional for constrained devices.
+///
+/// `min_version`, `cipher_suites` and `require_client_auth` are honored by
+/// the real rustls `ServerConfig` that [`TlsServer::start`] builds - they
+/// are distinct from [`TlsConnection::perform_handshake`], which remains a
+/// bare-TCP stub used only by [`TlsClient::connect`].
+pub struct TlsServerBuilder {
+    bind_addr: Option<std::net::SocketAddr>,
+    cert_path: Option<std::path::PathBuf>,
+    key_path: Option<std::path::PathBuf>,
+    client_ca_path: Option<std::path::PathBuf>,
+    min_version: TlsVersion,
+    cipher_suites: Vec<CipherSuite>,
+    require_client_auth: bool,
+}
+
+impl Default for TlsServerBuilder {
+    fn default() -> Self {
+        Self {
+            bind_addr: None,
+            cert_path: None,
+            key_path: None,
+            client_ca_path: None,
+            min_version: TlsVersion::Tls13,
+            cipher_suites: Vec::new(),
+            require_client_auth: true,
+        }
+    }
+}
+
+impl TlsServerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind_addr(mut self, bind_addr: std::net::SocketAddr) -> Self {
+        self.bind_addr = Some(bind_addr);
+        self
+    }
+
+    pub fn cert_path(mut self, cert_path: impl Into<std::path::PathBuf>) -> Self {
+        self.cert_path = Some(cert_path.into());
+        self
+    }
+
+    pub fn key_path(mut self, key_path: impl Into<std::path::PathBuf>) -> Self {
+        self.key_path = Some(key_path.into());
+        self
+    }
+
+    pub fn client_ca_path(mut self, client_ca_path: impl Into<std::path::PathBuf>) -> Self {
+        self.client_ca_path = Some(client_ca_path.into());
+        self
+    }
+
+    /// Restrict the minimum TLS protocol version the server negotiates.
+    /// Defaults to TLS 1.3; pass [`TlsVersion::Tls12`] to also allow
+    /// constrained RISC-V devices whose TLS stack doesn't support 1.3 yet.
+    pub fn min_version(mut self, min_version: TlsVersion) -> Self {
+        self.min_version = min_version;
+        self
+    }
+
+    /// Restrict the cipher suites the server is willing to negotiate.
+    /// Leaving this empty (the default) allows the full set supported by
+    /// `min_version`.
+    pub fn cipher_suites(mut self, cipher_suites: Vec<CipherSuite>) -> Self {
+        self.cipher_suites = cipher_suites;
+        self
+    }
+
+    /// Whether connecting clients must present a certificate signed by
+    /// `client_ca_path`. Defaults to `true`; set `false` to allow
+    /// unauthenticated clients to complete the TLS handshake.
+    pub fn require_client_auth(mut self, require_client_auth: bool) -> Self {
+        self.require_client_auth = require_client_auth;
+        self
+    }
+
+    /// Read and parse the configured PEM files and build the server.
+    /// Accepts PKCS8, PKCS1 (RSA) and SEC1 (EC) private keys alike - a
+    /// PKCS1 or SEC1 PEM key is handed to rustls under its own key type, and
+    /// any other key goes through `TlsUtils::parse_private_key`.
+    pub fn build(self) -> Result<TlsServer> {
+        let bind_addr = self
+            .bind_addr
+            .ok_or_else(|| anyhow::anyhow!("TlsServerBuilder: bind_addr is required"))?;
+        let cert_path = self
+            .cert_path
+            .ok_or_else(|| anyhow::anyhow!("TlsServerBuilder: cert_path is required"))?;
+        let key_path = self
+            .key_path
+            .ok_or_else(|| anyhow::anyhow!("TlsServerBuilder: key_path is required"))?;
+        let client_ca_path = self
+            .client_ca_path
+            .ok_or_else(|| anyhow::anyhow!("TlsServerBuilder: client_ca_path is required"))?;
+
+        let cert_bytes = std::fs::read(&cert_path)
+            .with_context(|| format!("Failed to read certificate from {}", cert_path.display()))?;
+        let key_bytes = std::fs::read(&key_path)
+            .with_context(|| format!("Failed to read private key from {}", key_path.display()))?;
+        let client_ca_bytes = std::fs::read(&client_ca_path).with_context(|| {
+            format!("Failed to read client CA certificate from {}", client_ca_path.display())
+        })?;
+
+        let server_cert = TlsUtils::parse_certificate(&cert_bytes)
+            .with_context(|| "Failed to parse certificate")?;
+        let server_key = match pem::parse(&key_bytes) {
+            Ok(key_pem) if key_pem.tag() == "RSA PRIVATE KEY" => PrivateKeyDer::from(
+                rustls_pki_types::PrivatePkcs1KeyDer::from(key_pem.contents().to_vec()),
+            ),
+            Ok(key_pem) if key_pem.tag() == "EC PRIVATE KEY" => PrivateKeyDer::from(
+                rustls_pki_types::PrivateSec1KeyDer::from(key_pem.contents().to_vec()),
+            ),
+            _ => TlsUtils::parse_private_key(&key_bytes)
+                .with_context(|| "Failed to parse private key")?,
+        };
+        let client_ca = TlsUtils::parse_certificates(&client_ca_bytes)
+            .with_context(|| "Failed to parse client CA certificates")?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No CA certificate found in PEM file"))?;
+
+        Ok(TlsServer {
+            bind_addr,
+            server_cert,
+            server_key,
+            client_ca,
+            min_version: self.min_version,
+            cipher_suites: self.cipher_suites,
+            require_client_auth: self.require_client_auth,
+        })
+    }
 }
 
 /// Custom TLS Client implementation
@@ -120,6 +307,10 @@ pub struct MessageContextWithKey {
     pub connection_id: String,
     pub message: Option<ClientMessage>,
     pub reply_fn: Option<Box<dyn Fn(ServerMessage) -> Result<()> + Send + Sync>>,
+    /// Subject CN of the client certificate presented during the mTLS
+    /// handshake, used to cross-check the identity claimed in enrollment
+    /// payloads against what the TLS layer actually verified
+    pub peer_subject_cn: Option<String>,
 }
 
 impl MessageContextWithKey {
@@ -130,6 +321,7 @@ impl MessageContextWithKey {
             connection_id,
             message: None,
             reply_fn: None,
+            peer_subject_cn: None,
         }
     }
 
@@ -143,6 +335,16 @@ impl MessageContextWithKey {
         &self.public_key
     }
 
+    /// Get the subject CN from the client's TLS certificate, if one was presented
+    pub fn peer_subject_cn(&self) -> Option<&str> {
+        self.peer_subject_cn.as_deref()
+    }
+
+    /// Set the subject CN parsed from the client's TLS certificate
+    pub fn set_peer_subject_cn(&mut self, subject_cn: String) {
+        self.peer_subject_cn = Some(subject_cn);
+    }
+
     /// Reply to the client
     pub fn reply(&self, message: ServerMessage) -> Result<()> {
         if let Some(reply_fn) = &self.reply_fn {
@@ -189,6 +391,11 @@ pub struct GatewayServerConfig {
     pub bind_addr: std::net::SocketAddr,
     pub identity: ServerIdentity,
     pub client_ca: CertificateDer<'static>,
+    /// When set, the server's certificate/key and per-connection client CA
+    /// are picked per-SNI from this resolver instead of the static
+    /// `identity`/`client_ca` above, letting one gateway front several
+    /// tenants each with their own TLS identity.
+    pub cert_resolver: Option<Arc<CertResolver>>,
     pub on_client_connect: Arc<OnClientConnectWithKey>,
     pub on_client_disconnect: Arc<OnClientDisconnectWithKey>,
     pub on_client_message: Arc<OnClientMessageWithKey>,
@@ -625,11 +832,13 @@ impl Server {
 }
 
 impl TlsServer {
-    /// Create a new TLS server
+    /// Create a new TLS server. Prefer [`TlsServerBuilder`], which reads and
+    /// parses PEM files directly and also lets callers configure the
+    /// minimum TLS version, cipher suites and client auth requirement.
     pub fn new(
         bind_addr: std::net::SocketAddr,
         server_cert: CertificateDer<'static>,
-        server_key: PrivatePkcs8KeyDer<'static>,
+        server_key: PrivateKeyDer<'static>,
         client_ca: CertificateDer<'static>,
     ) -> Self {
         Self {
@@ -637,11 +846,55 @@ impl TlsServer {
             server_cert,
             server_key,
             client_ca,
+            min_version: TlsVersion::Tls13,
+            cipher_suites: Vec::new(),
+            require_client_auth: true,
+        }
+    }
+
+    /// Start building a [`TlsServer`] via [`TlsServerBuilder`].
+    pub fn builder() -> TlsServerBuilder {
+        TlsServerBuilder::new()
+    }
+
+    /// Build the `rustls::ServerConfig` this server's handshake uses,
+    /// honoring the configured minimum TLS version, cipher suites and
+    /// client auth requirement.
+    fn build_server_config(&self) -> Result<rustls::ServerConfig> {
+        let mut provider = rustls::crypto::ring::default_provider();
+        if !self.cipher_suites.is_empty() {
+            provider.cipher_suites = self.cipher_suites.iter().map(|c| c.to_rustls()).collect();
+        }
+
+        let versions_builder = rustls::ServerConfig::builder_with_provider(Arc::new(provider))
+            .with_protocol_versions(self.min_version.rustls_protocol_versions())
+            .map_err(|e| anyhow::anyhow!("Failed to configure TLS protocol versions: {:?}", e))?;
+
+        let config = if self.require_client_auth {
+            let mut client_roots = rustls::RootCertStore::empty();
+            client_roots.add(self.client_ca.clone()).map_err(|e| {
+                anyhow::anyhow!("Failed to add client CA to root store: {:?}", e)
+            })?;
+            let client_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(client_roots))
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build client certificate verifier: {:?}", e))?;
+            versions_builder.with_client_cert_verifier(client_verifier)
+        } else {
+            versions_builder.with_no_client_auth()
         }
+        .with_single_cert(vec![self.server_cert.clone()], self.server_key.clone_key())
+        .map_err(|e| anyhow::anyhow!("Failed to create ServerConfig: {:?}", e))?;
+
+        Ok(config)
     }
 
     /// Start the TLS server
     pub async fn start(&self) -> Result<()> {
+        use tokio_rustls::TlsAcceptor;
+
+        let server_config = self.build_server_config()?;
+        let acceptor = TlsAcceptor::from(Arc::new(server_config));
+
         let listener = tokio::net::TcpListener::bind(self.bind_addr).await?;
         log::info!("TLS Server listening on {}", self.bind_addr);
 
@@ -649,8 +902,15 @@ impl TlsServer {
             match listener.accept().await {
                 Ok((stream, addr)) => {
                     log::info!("New connection from {}", addr);
-                    let connection = TlsConnection::new(stream);
-                    self.handle_connection(connection).await?;
+                    match acceptor.accept(stream).await {
+                        Ok(tls_stream) => {
+                            log::info!("TLS handshake completed for {}", addr);
+                            self.handle_connection(tls_stream).await?;
+                        }
+                        Err(e) => {
+                            log::error!("TLS handshake failed for {}: {}", addr, e);
+                        }
+                    }
                 }
                 Err(e) => {
                     log::error!("Failed to accept connection: {}", e);
@@ -660,14 +920,13 @@ impl TlsServer {
     }
 
     /// Handle a new TLS connection
-    async fn handle_connection(&self, mut connection: TlsConnection) -> Result<()> {
-        // Perform TLS handshake
-        connection.perform_handshake().await?;
-        
-        // Handle the connection
+    async fn handle_connection(
+        &self,
+        mut tls_stream: tokio_rustls::server::TlsStream<TcpStream>,
+    ) -> Result<()> {
         loop {
             let mut buffer = [0; 1024];
-            match connection.stream.read(&mut buffer).await {
+            match tls_stream.read(&mut buffer).await {
                 Ok(0) => {
                     log::info!("Connection closed by client");
                     break;
@@ -675,7 +934,7 @@ impl TlsServer {
                 Ok(n) => {
                     log::debug!("Received {} bytes", n);
                     // Echo back the data
-                    connection.stream.write_all(&buffer[..n]).await?;
+                    tls_stream.write_all(&buffer[..n]).await?;
                 }
                 Err(e) => {
                     log::error!("Error reading from connection: {}", e);
@@ -683,7 +942,7 @@ impl TlsServer {
                 }
             }
         }
-        
+
         Ok(())
     }
 }
@@ -914,27 +1173,67 @@ impl GatewayServer {
         println!("[DEBUG] After logging TLS server configuration");
         println!("[DEBUG] About to create ServerConfig");
         
-        // Create TLS server configuration without client certificate verification initially
+        // With a static identity (no cert resolver), require the peer to
+        // present a certificate signed by our pinned client CA, so only
+        // devices holding a key we've issued can reach the
+        // enrollment/control handshake at all.
         println!("[DEBUG] Calling ServerConfig::builder()");
         println!("[DEBUG] Certificate: {:?}", self.config.identity.certificate());
         println!("[DEBUG] Private key: {:?}", self.config.identity.private_key());
+
+        if let Some(resolver) = &self.config.cert_resolver {
+            // The per-tenant client CA varies by SNI name, and rustls builds
+            // its client certificate verifier once per `ServerConfig` rather
+            // than per connection. `LazyConfigAcceptor` lets us peek the
+            // ClientHello's SNI before the handshake proper begins, so we
+            // build a fresh `ServerConfig` per connection with a real
+            // `WebPkiClientVerifier` rooted at that tenant's actual CA -
+            // genuine chain verification, not a post-hoc DN string compare.
+            let listener = tokio::net::TcpListener::bind(self.config.bind_addr).await?;
+            log::info!("Gateway TLS Server listening on {} (per-tenant)", self.config.bind_addr);
+
+            loop {
+                match listener.accept().await {
+                    Ok((stream, addr)) => {
+                        if let Err(e) = self.accept_tenant_connection(stream, addr, resolver.clone()).await {
+                            log::error!("Failed to handle connection from {}: {}", addr, e);
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to accept connection: {}", e);
+                    }
+                }
+            }
+        }
+
+        let mut client_roots = rustls::RootCertStore::empty();
+        client_roots.add(self.config.client_ca.clone()).map_err(|e| {
+            log::error!("Failed to add client CA to root store: {:?}", e);
+            e
+        })?;
+        let client_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(client_roots))
+            .build()
+            .map_err(|e| {
+                log::error!("Failed to build client certificate verifier: {:?}", e);
+                anyhow::anyhow!("Failed to build client certificate verifier: {:?}", e)
+            })?;
+
         let server_config = ServerConfig::builder()
-            .with_no_client_auth()
+            .with_client_cert_verifier(client_verifier)
             .with_single_cert(
                 vec![self.config.identity.certificate().clone()],
                 rustls_pki_types::PrivateKeyDer::from(self.config.identity.private_key().clone_key()),
             ).map_err(|e| {
-                println!("[DEBUG] ServerConfig creation failed: {:?}", e);
                 log::error!("Failed to create ServerConfig: {:?}", e);
                 e
             })?;
         println!("[DEBUG] ServerConfig created successfully");
-        
+
         log::info!("TLS server configuration created successfully");
-        
+
         let acceptor = TlsAcceptor::from(Arc::new(server_config));
         log::info!("TlsAcceptor created successfully");
-        
+
         let listener = tokio::net::TcpListener::bind(self.config.bind_addr).await?;
         log::info!("Gateway TLS Server listening on {}", self.config.bind_addr);
 
@@ -942,7 +1241,7 @@ impl GatewayServer {
             match listener.accept().await {
                 Ok((stream, addr)) => {
                     log::info!("New connection from {}", addr);
-                    
+
                     // Accept TLS connection
                     match acceptor.accept(stream).await {
                         Ok(tls_stream) => {
@@ -961,25 +1260,77 @@ impl GatewayServer {
         }
     }
 
+    /// Peek a connection's SNI name via rustls's two-stage acceptor, build a
+    /// `ServerConfig` with a client verifier rooted at that tenant's real CA
+    /// (falling back to rejecting the connection if no tenant resolves),
+    /// and complete the handshake against it.
+    async fn accept_tenant_connection(&self, stream: tokio::net::TcpStream, addr: std::net::SocketAddr, resolver: Arc<crate::cert_resolver::CertResolver>) -> Result<()> {
+        use rustls::server::Acceptor;
+        use tokio_rustls::LazyConfigAcceptor;
+
+        let start = LazyConfigAcceptor::new(Acceptor::default(), stream).await?;
+        let server_name = start.client_hello().server_name().map(str::to_string);
+
+        let Some(client_ca) = resolver.client_ca_for(server_name.as_deref()) else {
+            log::warn!("Rejecting connection from {}: no tenant configured for SNI {:?}", addr, server_name);
+            return Ok(());
+        };
+
+        let mut client_roots = rustls::RootCertStore::empty();
+        client_roots.add(client_ca).map_err(|e| {
+            log::error!("Failed to add tenant CA to root store: {:?}", e);
+            e
+        })?;
+        let client_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(client_roots))
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build client certificate verifier: {:?}", e))?;
+
+        let server_config = Arc::new(
+            ServerConfig::builder()
+                .with_client_cert_verifier(client_verifier)
+                .with_cert_resolver(resolver.clone()),
+        );
+
+        match start.into_stream(server_config).await {
+            Ok(tls_stream) => {
+                log::info!("TLS handshake completed for {} (tenant SNI {:?})", addr, server_name);
+                self.handle_tls_connection(tls_stream, addr).await
+            }
+            Err(e) => {
+                log::error!("TLS handshake failed for {}: {}", addr, e);
+                Ok(())
+            }
+        }
+    }
+
     /// Handle a real TLS connection
     async fn handle_tls_connection(&self, mut tls_stream: tokio_rustls::server::TlsStream<tokio::net::TcpStream>, addr: std::net::SocketAddr) -> Result<()> {
         use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        
-        // Extract client certificate and public key
+
         let peer_certs = tls_stream.get_ref().1.peer_certificates();
-        let public_key = if let Some(certs) = peer_certs {
-            if let Some(cert) = certs.first() {
-                // Extract public key from certificate
-                TlsUtils::extract_public_key(cert).unwrap_or_else(|_| vec![])
-            } else {
-                vec![]
-            }
-        } else {
-            vec![]
-        };
-        
+        let leaf_cert = peer_certs.and_then(|certs| certs.first());
+
+        // The client certificate chain (to the static `client_ca`, or to
+        // the resolved tenant's CA via `accept_tenant_connection`'s
+        // per-connection `WebPkiClientVerifier`) was already verified
+        // cryptographically during the handshake; rustls would have
+        // aborted the connection otherwise.
+
+        // Extract client public key and subject CN now that the
+        // certificate has been validated against the appropriate CA
+        let public_key = leaf_cert
+            .and_then(|cert| TlsUtils::extract_public_key(cert).ok())
+            .unwrap_or_default();
+        let peer_subject_cn = leaf_cert
+            .and_then(|cert| parse_x509_certificate(cert.as_ref()).ok())
+            .and_then(|(_, parsed)| {
+                parsed.subject().iter_common_name().next()
+                    .and_then(|cn| cn.as_str().ok())
+                    .map(str::to_string)
+            });
+
         log::info!("Client public key: {} bytes", public_key.len());
-        
+
         // Call on_client_connect callback with public key
         let auth_result = (self.config.on_client_connect)(public_key.clone()).await;
         match auth_result {
@@ -1008,7 +1359,10 @@ impl GatewayServer {
                         public_key.clone(),
                         format!("gateway-connection-{}", addr),
                     );
-                    
+                    if let Some(subject_cn) = peer_subject_cn.clone() {
+                        ctx.set_peer_subject_cn(subject_cn);
+                    }
+
                     // Parse CBOR message if possible
                     if let Ok(client_message) = minicbor::decode::<ClientMessage>(&buffer[..n]) {
                         ctx.set_message(client_message);